@@ -0,0 +1,159 @@
+//! Benchmarks parsing and both parts of every implemented day.
+//!
+//! ```text
+//! cargo bench --bench days -- [day...] [--year <year>] [--warm-up <ms>] [--measurement <ms>] [--strict]
+//!                             [--json <path>] [--csv <path>]
+//! ```
+//!
+//! The JSON and CSV summaries hold one row per day and phase, so two runs (e.g. on different
+//! branches) can be diffed directly.
+
+use std::env;
+use std::fs;
+use std::process::exit;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use advent_of_code_2018::bench::{BenchConfig, Stats};
+use advent_of_code_2018::days::DAYS;
+use advent_of_code_2018::{InputSource, ParseMode, DEFAULT_YEAR};
+
+#[derive(Serialize)]
+struct Row {
+    day: u8,
+    phase: &'static str,
+    #[serde(flatten)]
+    stats: Stats,
+}
+
+fn value<T: std::str::FromStr>(args: &mut impl Iterator<Item = String>, flag: &str) -> T {
+    args.next()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| {
+            eprintln!("{flag} requires a valid value");
+            exit(2);
+        })
+}
+
+fn to_csv(rows: &[Row]) -> String {
+    let mut csv =
+        String::from("day,phase,samples,iterations,mean_ns,median_ns,std_dev_ns,min_ns,max_ns\n");
+
+    for Row { day, phase, stats } in rows {
+        csv += &format!(
+            "{day},{phase},{},{},{:.1},{:.1},{:.1},{:.1},{:.1}\n",
+            stats.samples,
+            stats.iterations,
+            stats.mean_ns,
+            stats.median_ns,
+            stats.std_dev_ns,
+            stats.min_ns,
+            stats.max_ns
+        );
+    }
+
+    csv
+}
+
+fn write(path: &str, contents: String) {
+    if let Err(err) = fs::write(path, contents) {
+        eprintln!("Cannot write {path}: {err}");
+        exit(1);
+    }
+}
+
+fn main() {
+    let mut config = BenchConfig::default();
+    let mut selected: Vec<u8> = vec![];
+    let mut json_path = None;
+    let mut csv_path = None;
+    let mut mode = ParseMode::Lenient;
+    let mut year = DEFAULT_YEAR;
+
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            // Passed by `cargo bench` to every bench target.
+            "--bench" => {}
+            "--warm-up" => config.warm_up = Duration::from_millis(value(&mut args, &arg)),
+            "--measurement" => config.measurement = Duration::from_millis(value(&mut args, &arg)),
+            "--json" => json_path = Some(value::<String>(&mut args, &arg)),
+            "--csv" => csv_path = Some(value::<String>(&mut args, &arg)),
+            "--strict" => mode = ParseMode::Strict,
+            "--year" => year = value(&mut args, &arg),
+            _ => match arg.parse() {
+                Ok(day) => selected.push(day),
+                Err(_) => {
+                    eprintln!("Unknown argument: {arg}");
+                    exit(2);
+                }
+            },
+        }
+    }
+
+    let source = InputSource::default();
+    let mut rows = vec![];
+
+    println!(
+        "{:<5} {:<6} {:>12} {:>12} {:>12} {:>8}",
+        "Day", "Phase", "Mean", "Std dev", "Median", "Samples"
+    );
+
+    for day in DAYS
+        .iter()
+        .filter(|day| day.year == year)
+        .filter(|day| selected.is_empty() || selected.contains(&day.day))
+    {
+        let raw = match source.load(year, day.day) {
+            Ok(raw) => raw,
+            Err(err) => {
+                println!("{:02}    {err}", day.day);
+                continue;
+            }
+        };
+
+        let result = match (day.bench)(&raw, mode, &config) {
+            Ok(result) => result,
+            Err(err) => {
+                println!(
+                    "{:02}    {}: {err}",
+                    day.day,
+                    source.describe(year, day.day)
+                );
+                continue;
+            }
+        };
+
+        for (phase, stats) in [
+            ("parse", result.parse),
+            ("part1", result.part1),
+            ("part2", result.part2),
+        ] {
+            println!(
+                "{:<5} {:<6} {:>12} {:>12} {:>12} {:>8}",
+                format!("{:02}", day.day),
+                phase,
+                format!("{:.2?}", stats.mean()),
+                format!("{:.2?}", Duration::from_nanos(stats.std_dev_ns as u64)),
+                format!("{:.2?}", Duration::from_nanos(stats.median_ns as u64)),
+                stats.samples,
+            );
+
+            rows.push(Row {
+                day: day.day,
+                phase,
+                stats,
+            });
+        }
+    }
+
+    if let Some(path) = json_path {
+        write(&path, serde_json::to_string_pretty(&rows).unwrap());
+    }
+
+    if let Some(path) = csv_path {
+        write(&path, to_csv(&rows));
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::toml_file::{self, key, StateError};
+use crate::{Expected, DEFAULT_YEAR};
+
+/// Known puzzle answers, keyed by day then part:
+///
+/// ```toml
+/// [day01]
+/// part1 = "516"
+/// part2 = "71892"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct AnswerRegistry {
+    #[serde(flatten)]
+    days: BTreeMap<String, Expected>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: String },
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::Wrong { expected } => write!(f, "wrong, expected {expected}"),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// `answers.toml` for the repository's own year, `answers-<year>.toml` for other events.
+pub fn default_path(year: u16) -> PathBuf {
+    let file_name = if year == DEFAULT_YEAR {
+        "answers.toml".to_string()
+    } else {
+        format!("answers-{year}.toml")
+    };
+
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(file_name)
+}
+
+impl AnswerRegistry {
+    /// Reads the registry at `path`; a missing file is an empty registry.
+    pub fn load(path: &Path) -> Result<AnswerRegistry, StateError> {
+        toml_file::load(path)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StateError> {
+        toml_file::save(self, path)
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        let answers = self.days.get(&key(day))?;
+
+        match part {
+            1 => answers.part1.as_deref(),
+            2 => answers.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn verdict(&self, day: u8, part: u8, answer: &str) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == answer => Verdict::Correct,
+            Some(expected) => Verdict::Wrong {
+                expected: expected.to_string(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+
+    /// Stores `answer` for a part that has no known answer yet. Returns `false`, leaving the
+    /// registry untouched, if the part already has one: a known answer is only changed by hand.
+    pub fn record(&mut self, day: u8, part: u8, answer: &str) -> bool {
+        if self.get(day, part).is_some() {
+            return false;
+        }
+
+        let answers = self.days.entry(key(day)).or_default();
+
+        match part {
+            1 => answers.part1 = Some(answer.to_string()),
+            2 => answers.part2 = Some(answer.to_string()),
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGISTRY: &str = r#"
+[day01]
+part1 = "516"
+
+[day11]
+part1 = "243,34"
+part2 = "90,214,15"
+"#;
+
+    #[test]
+    fn verdicts() {
+        let registry: AnswerRegistry = toml::from_str(REGISTRY).unwrap();
+
+        assert_eq!(registry.verdict(1, 1, "516"), Verdict::Correct);
+        assert_eq!(
+            registry.verdict(11, 2, "90,214,16"),
+            Verdict::Wrong {
+                expected: "90,214,15".to_string()
+            }
+        );
+        assert_eq!(registry.verdict(1, 2, "71892"), Verdict::Unknown);
+        assert_eq!(registry.verdict(2, 1, "7872"), Verdict::Unknown);
+    }
+
+    #[test]
+    fn record_only_fills_unknown_parts() {
+        let mut registry: AnswerRegistry = toml::from_str(REGISTRY).unwrap();
+
+        assert!(registry.record(1, 2, "71892"));
+        assert!(registry.record(2, 1, "7872"));
+        assert!(!registry.record(11, 1, "1,1"));
+
+        let reloaded: AnswerRegistry =
+            toml::from_str(&toml::to_string(&registry).unwrap()).unwrap();
+
+        assert_eq!(reloaded.get(1, 2), Some("71892"));
+        assert_eq!(reloaded.get(2, 1), Some("7872"));
+        assert_eq!(reloaded.get(11, 1), Some("243,34"));
+    }
+}
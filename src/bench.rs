@@ -0,0 +1,137 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::{ParseMode, Parser, Result, Solution};
+
+#[derive(Debug, Copy, Clone)]
+pub struct BenchConfig {
+    /// How long to run a function before measuring it, also used to estimate its run time.
+    pub warm_up: Duration,
+    /// Roughly how long to spend measuring each function.
+    pub measurement: Duration,
+    pub min_samples: usize,
+    pub max_samples: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            warm_up: Duration::from_millis(500),
+            measurement: Duration::from_secs(3),
+            min_samples: 10,
+            max_samples: 100,
+        }
+    }
+}
+
+/// Per-iteration timings over all samples, in nanoseconds.
+#[derive(Debug, Copy, Clone, Serialize)]
+pub struct Stats {
+    pub samples: usize,
+    pub iterations: u64,
+    pub mean_ns: f64,
+    pub median_ns: f64,
+    pub std_dev_ns: f64,
+    pub min_ns: f64,
+    pub max_ns: f64,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<f64>, iterations: u64) -> Stats {
+        samples.sort_by(f64::total_cmp);
+
+        let n = samples.len();
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n as f64;
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2.0
+        } else {
+            samples[n / 2]
+        };
+
+        Stats {
+            samples: n,
+            iterations,
+            mean_ns: mean,
+            median_ns: median,
+            std_dev_ns: variance.sqrt(),
+            min_ns: samples[0],
+            max_ns: samples[n - 1],
+        }
+    }
+
+    pub fn mean(&self) -> Duration {
+        Duration::from_nanos(self.mean_ns as u64)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Serialize)]
+pub struct DayBench {
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+/// How many inputs are cloned ahead of a timed batch of iterations, so that memory stays at a few
+/// inputs however many iterations a sample takes.
+const BATCH_SIZE: usize = 16;
+
+/// Times `f`, cloning its input for every iteration outside of the timed section.
+///
+/// After warming up, each sample runs `f` enough times to fill its share of the measurement time,
+/// so fast functions are not dominated by timer resolution. The iterations run in small batches,
+/// each with its inputs cloned just before it.
+pub fn measure<I: Clone, O>(config: &BenchConfig, input: &I, f: impl Fn(I) -> O) -> Stats {
+    let mut warm_up_iterations = 0u32;
+    let warm_up_start = Instant::now();
+
+    while warm_up_iterations == 0 || warm_up_start.elapsed() < config.warm_up {
+        black_box(f(input.clone()));
+        warm_up_iterations += 1;
+    }
+
+    let estimate = warm_up_start.elapsed() / warm_up_iterations;
+
+    let n_samples = (config.measurement.as_nanos() / estimate.as_nanos().max(1))
+        .clamp(config.min_samples as u128, config.max_samples as u128) as usize;
+    let sample_time = config.measurement / n_samples as u32;
+    let iterations_per_sample =
+        (sample_time.as_nanos() / estimate.as_nanos().max(1)).max(1) as usize;
+
+    let mut samples = Vec::with_capacity(n_samples);
+
+    for _ in 0..n_samples {
+        let mut elapsed = Duration::ZERO;
+        let mut remaining = iterations_per_sample;
+
+        while remaining > 0 {
+            let batch = remaining.min(BATCH_SIZE);
+            let inputs = vec![input.clone(); batch];
+
+            let now = Instant::now();
+            for input in inputs {
+                black_box(f(black_box(input)));
+            }
+            elapsed += now.elapsed();
+
+            remaining -= batch;
+        }
+
+        samples.push(elapsed.as_nanos() as f64 / iterations_per_sample as f64);
+    }
+
+    Stats::from_samples(samples, (n_samples * iterations_per_sample) as u64)
+}
+
+/// Benchmarks parsing and each part of `S` separately. Fails if the input does not parse.
+pub fn bench<S: Solution>(raw: &str, mode: ParseMode, config: &BenchConfig) -> Result<DayBench> {
+    let (input, _) = crate::parse::<S>(raw, mode)?;
+
+    Ok(DayBench {
+        parse: measure(config, &raw, |raw| S::parse(raw, &mut Parser::new(mode))),
+        part1: measure(config, &input, S::part1),
+        part2: measure(config, &input, S::part2),
+    })
+}
@@ -0,0 +1,499 @@
+use std::env;
+use std::ops::RangeInclusive;
+use std::process::exit;
+use std::time::Duration;
+
+use advent_of_code_2018::answers::{self, AnswerRegistry, Verdict};
+use advent_of_code_2018::days;
+use advent_of_code_2018::example::PartCheck;
+use advent_of_code_2018::input::year_from_args;
+use advent_of_code_2018::report::{self, Baseline, Budget, Report};
+use advent_of_code_2018::{DayAnswers, Error, ExampleReport, InputError, InputSource, ParseMode};
+
+const USAGE: &str = "Usage: aoc run <day | from..to | from..=to | all>... [--year <year>] \
+                     [--input-dir <dir>] [--example | --input <path> | -] [--strict] [--check] [--record <day>:<part>] \
+                     [--report [--budget <ms>] [--total-budget <ms>] [--tolerance <percent>]] \
+                     [--save-baseline]";
+
+const LAST_DAY: u8 = 25;
+
+enum Status {
+    Solved(DayAnswers),
+    Checked(ExampleReport),
+    NotImplemented,
+    MissingInput(InputError),
+    /// The input was read, but the day could not parse it.
+    InvalidInput {
+        path: String,
+        err: Error,
+    },
+}
+
+fn parse_days(arg: &str) -> Option<RangeInclusive<u8>> {
+    if arg == "all" {
+        return Some(1..=LAST_DAY);
+    }
+
+    let range = if let Some((from, to)) = arg.split_once("..=") {
+        from.parse().ok()?..=to.parse().ok()?
+    } else if let Some((from, to)) = arg.split_once("..") {
+        from.parse().ok()?..=to.parse::<u8>().ok()?.checked_sub(1)?
+    } else {
+        let day = arg.parse().ok()?;
+        day..=day
+    };
+
+    if range.is_empty() || *range.start() == 0 || *range.end() > LAST_DAY {
+        return None;
+    }
+
+    Some(range)
+}
+
+fn run_day(year: u16, day: u8, source: &InputSource, mode: ParseMode) -> Status {
+    let Some(solution) = days::find(year, day) else {
+        return Status::NotImplemented;
+    };
+
+    let raw = match source.load(year, day) {
+        Ok(raw) => raw,
+        Err(err) => return Status::MissingInput(err),
+    };
+
+    let result = match source.expected(year, day) {
+        Ok(Some(expected)) => (solution.check_example)(&raw, &expected).map(Status::Checked),
+        Ok(None) => (solution.solve)(&raw, mode).map(Status::Solved),
+        Err(err) => return Status::MissingInput(err),
+    };
+
+    result.unwrap_or_else(|err| Status::InvalidInput {
+        path: source.describe(year, day),
+        err,
+    })
+}
+
+/// Multi-line answers (e.g. day 10's rendered message) do not fit in a table cell.
+fn cell(answer: &str) -> &str {
+    if answer.contains('\n') {
+        "(see below)"
+    } else {
+        answer
+    }
+}
+
+/// The answer and timing columns of a row in the table. Answers to the puzzle input are marked
+/// with their verdict from `registry`.
+fn columns(
+    day: u8,
+    status: &Status,
+    registry: Option<&AnswerRegistry>,
+) -> Option<([String; 2], [Option<Duration>; 3])> {
+    let answer = |part, answer: &Result<String, Error>| match (answer, registry) {
+        (Err(err), _) => err.to_string(),
+        (Ok(answer), Some(registry)) => {
+            format!("{} ({})", cell(answer), registry.verdict(day, part, answer))
+        }
+        (Ok(answer), None) => cell(answer).to_string(),
+    };
+
+    match status {
+        Status::Solved(answers) => Some((
+            [answer(1, &answers.part1), answer(2, &answers.part2)],
+            [
+                Some(answers.timings.input),
+                Some(answers.timings.part1),
+                Some(answers.timings.part2),
+            ],
+        )),
+        Status::Checked(report) => {
+            let answer = |check: &Option<PartCheck>| {
+                check.as_ref().map_or("-".to_string(), PartCheck::to_string)
+            };
+
+            Some((
+                [answer(&report.part1), answer(&report.part2)],
+                [
+                    Some(report.input_elapsed),
+                    report.part1.as_ref().map(|check| check.elapsed),
+                    report.part2.as_ref().map(|check| check.elapsed),
+                ],
+            ))
+        }
+        Status::NotImplemented | Status::MissingInput(_) | Status::InvalidInput { .. } => None,
+    }
+}
+
+fn print_table(results: &[(u8, Status)], registry: Option<&AnswerRegistry>) {
+    let answer_width = results
+        .iter()
+        .flat_map(|(day, status)| columns(*day, status, registry))
+        .flat_map(|(answers, _)| answers.map(|answer| answer.len()))
+        .max()
+        .unwrap_or(0)
+        .max("Part 1".len());
+
+    println!(
+        "{:<5} {:<answer_width$} {:<answer_width$} {:>10} {:>10} {:>10}",
+        "Day", "Part 1", "Part 2", "Input", "Part 1", "Part 2"
+    );
+
+    let mut total = Duration::ZERO;
+
+    for (day, status) in results {
+        match status {
+            Status::NotImplemented => println!("{day:02}    not implemented"),
+            Status::MissingInput(err) => println!("{day:02}    {err}"),
+            Status::InvalidInput { path, err } => println!("{day:02}    {path}: {err}"),
+            _ => {
+                let Some(([part1, part2], timings)) = columns(*day, status, registry) else {
+                    continue;
+                };
+
+                total += timings.iter().flatten().sum::<Duration>();
+
+                let [input, part1_elapsed, part2_elapsed] =
+                    timings.map(|elapsed| elapsed.map_or("-".to_string(), |e| format!("{e:.2?}")));
+
+                println!(
+                    "{:<5} {:<answer_width$} {:<answer_width$} {:>10} {:>10} {:>10}",
+                    format!("{day:02}"),
+                    part1,
+                    part2,
+                    input,
+                    part1_elapsed,
+                    part2_elapsed,
+                );
+            }
+        }
+    }
+
+    println!("Total: {:.2?}", total);
+
+    for (day, status) in results {
+        match status {
+            Status::Solved(answers) if answers.skipped > 0 => println!(
+                "Day {day:02}: skipped {} malformed lines (run with --strict to list them)",
+                answers.skipped
+            ),
+            _ => {}
+        }
+    }
+
+    for (day, status) in results {
+        let Status::Solved(answers) = status else {
+            continue;
+        };
+
+        for (part, answer) in [(1, &answers.part1), (2, &answers.part2)] {
+            if let Some(answer) = answer.as_ref().ok().filter(|answer| answer.contains('\n')) {
+                println!();
+                println!("--- Day {day:02} part {part} ---{answer}");
+            }
+        }
+    }
+}
+
+/// Every answer to the puzzle input with its verdict. Parts that failed have no answer to judge.
+fn verdicts<'a>(
+    results: &'a [(u8, Status)],
+    registry: &'a AnswerRegistry,
+) -> impl Iterator<Item = (u8, u8, &'a str, Verdict)> + 'a {
+    results.iter().flat_map(move |(day, status)| {
+        let answers = match status {
+            Status::Solved(answers) => vec![(1, &answers.part1), (2, &answers.part2)],
+            _ => vec![],
+        };
+
+        answers
+            .into_iter()
+            .filter_map(|(part, answer)| Some((part, answer.as_deref().ok()?)))
+            .map(move |(part, answer)| (*day, part, answer, registry.verdict(*day, part, answer)))
+    })
+}
+
+/// Prints how many answers are correct, wrong or unknown. Returns `false` if any are wrong.
+fn check(results: &[(u8, Status)], registry: &AnswerRegistry) -> bool {
+    let (mut correct, mut wrong, mut unknown) = (0, 0, 0);
+
+    for (_, _, _, verdict) in verdicts(results, registry) {
+        match verdict {
+            Verdict::Correct => correct += 1,
+            Verdict::Wrong { .. } => wrong += 1,
+            Verdict::Unknown => unknown += 1,
+        }
+    }
+
+    println!("Check: {correct} correct, {wrong} wrong, {unknown} unknown");
+
+    wrong == 0
+}
+
+/// `<day>:<part>`, e.g. `12:2`.
+fn parse_part(arg: &str) -> Option<(u8, u8)> {
+    let (day, part) = arg.split_once(':')?;
+    let (day, part) = (day.parse().ok()?, part.parse().ok()?);
+
+    ((1..=LAST_DAY).contains(&day) && (1..=2).contains(&part)).then_some((day, part))
+}
+
+/// Writes the answer to `part` of `day`, which the site accepted, into the registry. Only a part
+/// with no known answer is recorded.
+fn record(
+    year: u16,
+    (day, part): (u8, u8),
+    results: &[(u8, Status)],
+    registry: &mut AnswerRegistry,
+) -> Result<(), String> {
+    let answer = verdicts(results, registry)
+        .find(|(d, p, _, _)| (*d, *p) == (day, part))
+        .map(|(_, _, answer, _)| answer.to_string())
+        .ok_or_else(|| format!("Day {day:02} part {part} has no answer to record"))?;
+
+    if !registry.record(day, part, &answer) {
+        return Err(format!(
+            "Day {day:02} part {part} already has an answer, change it in {} by hand",
+            answers::default_path(year).display()
+        ));
+    }
+
+    registry
+        .save(&answers::default_path(year))
+        .map_err(|err| err.to_string())?;
+
+    println!("Recorded day {day:02} part {part}: {answer}");
+
+    Ok(())
+}
+
+fn print_report(report: &Report, budget: &Budget) {
+    println!();
+    println!(
+        "{:<5} {:<5} {:>10} {:>7} {:>10}",
+        "Rank", "Day", "Time", "Share", "Baseline"
+    );
+
+    for (rank, row) in report.rows.iter().enumerate() {
+        let mut flags = vec![];
+
+        if row.over_budget {
+            flags.push(format!("over budget ({:.2?})", budget.per_day));
+        }
+
+        if let (true, Some(baseline)) = (row.regressed, row.baseline) {
+            let change = 100.0 * (row.elapsed.as_secs_f64() / baseline.as_secs_f64() - 1.0);
+            flags.push(format!("slower than baseline (+{change:.1}%)"));
+        }
+
+        let line = format!(
+            "{:<5} {:<5} {:>10} {:>6.1}% {:>10} {}",
+            rank + 1,
+            format!("{:02}", row.day),
+            format!("{:.2?}", row.elapsed),
+            row.share,
+            row.baseline
+                .map_or("-".to_string(), |baseline| format!("{baseline:.2?}")),
+            flags.join(", ")
+        );
+
+        println!("{}", line.trim_end());
+    }
+
+    println!(
+        "Total: {:.2?} (budget {:.2?}){}",
+        report.total,
+        budget.total,
+        if report.over_total_budget {
+            " over budget"
+        } else {
+            ""
+        }
+    );
+
+    if cfg!(debug_assertions) {
+        println!("Note: this is a debug build, run with --release for representative timings");
+    }
+}
+
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != flag);
+
+    args.len() != len
+}
+
+fn take_value(args: &mut Vec<String>, flag: &str) -> Option<String> {
+    let i = args.iter().position(|arg| arg == flag)?;
+    args.remove(i);
+
+    if i == args.len() {
+        eprintln!("{flag} requires a value");
+        exit(2);
+    }
+
+    Some(args.remove(i))
+}
+
+fn take_number(args: &mut Vec<String>, flag: &str) -> Option<f64> {
+    let value = take_value(args, flag)?;
+
+    match value.parse::<f64>() {
+        Ok(number) if number >= 0.0 => Some(number),
+        _ => {
+            eprintln!("{flag} requires a non-negative number, got {value}");
+            exit(2);
+        }
+    }
+}
+
+fn main() {
+    let mut args: Vec<_> = env::args().skip(1).collect();
+
+    let check_flag = take_flag(&mut args, "--check");
+    let record_part = take_value(&mut args, "--record").map(|value| {
+        parse_part(&value).unwrap_or_else(|| {
+            eprintln!("--record requires <day>:<part>, e.g. 12:2, got {value}");
+            exit(2);
+        })
+    });
+    let report_flag = take_flag(&mut args, "--report");
+    let save_baseline_flag = take_flag(&mut args, "--save-baseline");
+
+    let mut budget = Budget::default();
+
+    if let Some(ms) = take_number(&mut args, "--budget") {
+        budget.per_day = Duration::from_secs_f64(ms / 1000.0);
+    }
+
+    if let Some(ms) = take_number(&mut args, "--total-budget") {
+        budget.total = Duration::from_secs_f64(ms / 1000.0);
+    }
+
+    if let Some(percent) = take_number(&mut args, "--tolerance") {
+        budget.tolerance = percent / 100.0;
+    }
+
+    let year = year_from_args(&mut args).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        exit(2);
+    });
+    let source = InputSource::from_args(&mut args).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        exit(2);
+    });
+    let mode = ParseMode::from_args(&mut args);
+
+    if args.first().map(String::as_str) != Some("run") || args.len() < 2 {
+        eprintln!("{USAGE}");
+        exit(2);
+    }
+
+    let mut selected = Vec::new();
+
+    for arg in &args[1..] {
+        let Some(range) = parse_days(arg) else {
+            eprintln!("Invalid day selection: {arg}");
+            eprintln!("{USAGE}");
+            exit(2);
+        };
+
+        selected.extend(range);
+    }
+
+    selected.sort_unstable();
+    selected.dedup();
+
+    if matches!(source, InputSource::Stdin | InputSource::File(_)) && selected.len() > 1 {
+        eprintln!("Reading from stdin (-) or --input needs a single day");
+        exit(2);
+    }
+
+    if let Some((day, _)) = record_part.filter(|(day, _)| selected != [*day]) {
+        eprintln!("--record only records from a run of day {day} alone");
+        exit(2);
+    }
+
+    if (check_flag || record_part.is_some() || report_flag || save_baseline_flag)
+        && !source.is_puzzle_input()
+    {
+        eprintln!("--check, --record, --report and --save-baseline only apply to the puzzle input");
+        exit(2);
+    }
+
+    let mut registry = if source.is_puzzle_input() {
+        Some(
+            AnswerRegistry::load(&answers::default_path(year)).unwrap_or_else(|err| {
+                eprintln!("{err}");
+                exit(2);
+            }),
+        )
+    } else {
+        None
+    };
+
+    let results: Vec<_> = selected
+        .into_iter()
+        .map(|day| (day, run_day(year, day, &source, mode)))
+        .collect();
+
+    print_table(&results, registry.as_ref());
+
+    let mut failed = results.iter().any(|(_, status)| match status {
+        Status::Solved(answers) => [&answers.part1, &answers.part2]
+            .into_iter()
+            .any(|answer| matches!(answer, Err(err) if *err != Error::Unsolved)),
+        Status::Checked(report) => !report.passed(),
+        Status::InvalidInput { .. } => true,
+        Status::NotImplemented | Status::MissingInput(_) => false,
+    });
+
+    if let Some(registry) = registry.as_mut() {
+        if check_flag {
+            failed |= !check(&results, registry);
+        }
+
+        if let Some(part) = record_part {
+            if let Err(err) = record(year, part, &results, registry) {
+                eprintln!("{err}");
+                exit(1);
+            }
+        }
+    }
+
+    let timings: Vec<_> = results
+        .iter()
+        .filter_map(|(day, status)| match status {
+            Status::Solved(answers) => Some((*day, answers.timings)),
+            _ => None,
+        })
+        .collect();
+
+    let baseline_path = report::default_baseline_path(year);
+
+    if report_flag || save_baseline_flag {
+        let mut baseline = Baseline::load(&baseline_path).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            exit(2);
+        });
+
+        if report_flag {
+            print_report(&Report::new(&timings, &budget, &baseline), &budget);
+        }
+
+        if save_baseline_flag {
+            for (day, timings) in &timings {
+                baseline.set(*day, timings);
+            }
+
+            if let Err(err) = baseline.save(&baseline_path) {
+                eprintln!("{err}");
+                exit(1);
+            }
+
+            println!("Saved baseline to {}", baseline_path.display());
+        }
+    }
+
+    if failed {
+        exit(1);
+    }
+}
@@ -1,58 +1,64 @@
-use std::fs;
-use std::time::Instant;
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
 
 type Int = i32;
 type InputType = Vec<Int>;
 
-fn read_input() -> InputType {
-    fs::read_to_string("./src/bin/day01/input.txt")
-        .unwrap()
-        .trim()
-        .lines()
-        .flat_map(|s| s.parse::<Int>())
-        .collect()
-}
-
-fn part1(input: InputType) -> Int {
-    input.iter().sum()
-}
-
 const MAX_VALUE: usize = 1_000_000;
 
-fn part2(input: InputType) -> Int {
-    let mut current = MAX_VALUE as Int;
-    let mut history = [false; MAX_VALUE * 2];
+pub struct Day01;
 
-    for i in input.iter().cycle() {
-        current += i;
+impl Solution for Day01 {
+    const DAY: u8 = 1;
 
-        if history[current as usize] {
-            return current - MAX_VALUE as Int;
-        }
+    type Input = InputType;
+    type Output1 = Int;
+    type Output2 = Int;
 
-        history[current as usize] = true;
+    fn parse(input: &str, parser: &mut Parser) -> Result<InputType> {
+        Ok(input
+            .trim()
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                parser.line(line.parse().map_err(|err| Error::parse(i, line, err)))
+            })
+            .collect())
     }
 
-    panic!()
-}
+    fn part1(input: InputType) -> Result<Int> {
+        Ok(input.iter().sum())
+    }
 
-pub fn main() {
-    let input = read_input();
+    fn part2(input: InputType) -> Result<Int> {
+        let mut current = MAX_VALUE as Int;
+        let mut history = [false; MAX_VALUE * 2];
+
+        for i in input.iter().cycle() {
+            current += i;
 
-    let mut now = Instant::now();
-    let part1 = part1(input.clone());
-    let part1_elapsed = now.elapsed();
+            let seen = usize::try_from(current)
+                .ok()
+                .and_then(|index| history.get_mut(index))
+                .ok_or_else(|| {
+                    Error::OutOfRange(format!(
+                        "frequency {} is beyond ±{MAX_VALUE}",
+                        current - MAX_VALUE as Int
+                    ))
+                })?;
 
-    now = Instant::now();
-    let part2 = part2(input);
-    let part2_elapsed = now.elapsed();
+            if *seen {
+                return Ok(current - MAX_VALUE as Int);
+            }
 
-    println!("--- Day 01 ---");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
-    println!("Part 1 took: {:.2?}", part1_elapsed);
-    println!("Part 2 took: {:.2?}", part2_elapsed);
+            *seen = true;
+        }
 
-    assert_eq!(part1, 516);
-    assert_eq!(part2, 71892);
+        Err(Error::NoSolution(
+            "there are no frequency changes".to_string(),
+        ))
+    }
+}
+
+pub fn main() {
+    run::<Day01>();
 }
@@ -1,82 +1,84 @@
-use std::fs;
-use std::time::Instant;
-
 use itertools::Itertools;
 
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
+
 type Int = u16;
 type InputType = Vec<String>;
 
-fn read_input() -> InputType {
-    fs::read_to_string("./src/bin/day02/input.txt")
-        .unwrap()
-        .trim()
-        .lines()
-        .map(str::to_string)
-        .collect()
-}
+pub struct Day02;
 
-fn part1(input: InputType) -> Int {
-    let (twos, threes) = input.iter().fold((0, 0), |(twos, threes), s| {
-        let counts: Vec<(usize, char)> = s.chars().sorted_unstable().dedup_with_count().collect();
+impl Solution for Day02 {
+    const DAY: u8 = 2;
 
-        (
-            twos + Int::from(counts.iter().any(|(count, _)| count == &2)),
-            threes + Int::from(counts.iter().any(|(count, _)| count == &3)),
-        )
-    });
+    type Input = InputType;
+    type Output1 = Int;
+    type Output2 = String;
 
-    twos * threes
-}
+    fn parse(input: &str, parser: &mut Parser) -> Result<InputType> {
+        let mut ids: InputType = vec![];
 
-fn part2(input: InputType) -> String {
-    let mut answer = String::with_capacity(input.first().unwrap().capacity());
+        for (i, line) in input.trim().lines().enumerate() {
+            if let Some(first) = ids.first().filter(|first| first.len() != line.len()) {
+                parser.reject(Error::parse(
+                    i,
+                    line,
+                    format!("expected an ID of length {}", first.len()),
+                ));
+                continue;
+            }
 
-    for i in 0..input.len() {
-        'outer: for j in (i + 1)..input.len() {
-            let x = input.get(i).unwrap();
-            let y = input.get(j).unwrap();
+            ids.push(line.to_string());
+        }
 
-            let mut difference = 0;
+        Ok(ids)
+    }
 
-            answer.clear();
+    fn part1(input: InputType) -> Result<Int> {
+        let (twos, threes) = input.iter().fold((0, 0), |(twos, threes), s| {
+            let counts: Vec<(usize, char)> =
+                s.chars().sorted_unstable().dedup_with_count().collect();
 
-            for (xx, yy) in x.chars().zip_eq(y.chars()) {
-                if xx == yy {
-                    answer.push(xx);
-                    continue;
-                }
+            (
+                twos + Int::from(counts.iter().any(|(count, _)| count == &2)),
+                threes + Int::from(counts.iter().any(|(count, _)| count == &3)),
+            )
+        });
 
-                difference += 1;
+        Ok(twos * threes)
+    }
 
-                if difference > 1 {
-                    continue 'outer;
-                }
-            }
+    fn part2(input: InputType) -> Result<String> {
+        let mut answer = String::with_capacity(input.first().map_or(0, String::len));
 
-            return answer;
-        }
-    }
+        for (i, x) in input.iter().enumerate() {
+            'outer: for y in &input[(i + 1)..] {
+                let mut difference = 0;
 
-    panic!()
-}
+                answer.clear();
 
-pub fn main() {
-    let input = read_input();
+                for (xx, yy) in x.chars().zip_eq(y.chars()) {
+                    if xx == yy {
+                        answer.push(xx);
+                        continue;
+                    }
 
-    let mut now = Instant::now();
-    let part1 = part1(input.clone());
-    let part1_elapsed = now.elapsed();
+                    difference += 1;
 
-    now = Instant::now();
-    let part2 = part2(input);
-    let part2_elapsed = now.elapsed();
+                    if difference > 1 {
+                        continue 'outer;
+                    }
+                }
 
-    println!("--- Day 02 ---");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
-    println!("Part 1 took: {:.2?}", part1_elapsed);
-    println!("Part 2 took: {:.2?}", part2_elapsed);
+                return Ok(answer);
+            }
+        }
 
-    assert_eq!(part1, 7872);
-    assert_eq!(part2, "tjxmoewpdkyaihvrndfluwbzc");
+        Err(Error::NoSolution(
+            "no two IDs differ by exactly one character".to_string(),
+        ))
+    }
+}
+
+pub fn main() {
+    run::<Day02>();
 }
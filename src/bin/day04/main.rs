@@ -1,104 +1,144 @@
+use std::collections::HashMap;
+
 use itertools::Itertools;
 use regex::Regex;
-use std::collections::HashMap;
-use std::fs;
-use std::num::ParseIntError;
-use std::time::Instant;
+
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
 
 type Int = u64;
 type InputType = HashMap<u16, [u16; 60]>;
 
-fn read_input() -> InputType {
-    let re = Regex::new(r"^\[.+ \d+:(\d+)\] (.+)$").unwrap();
-
-    fs::read_to_string("./src/bin/day04/input.txt")
-        .unwrap()
-        .trim()
-        .lines()
-        .sorted_unstable()
-        .flat_map(|line| re.captures(line))
-        .flat_map(|cap| -> Result<(u8, String), ParseIntError> {
-            Ok((cap[1].parse()?, cap[2].to_string()))
-        })
-        .fold(
-            (HashMap::new() as InputType, 0, 0),
-            |(mut guards, mut curr_guard, mut curr_time_start), (minute, message)| {
-                match message.as_str() {
-                    "falls asleep" => curr_time_start = minute,
-                    "wakes up" => {
-                        guards.entry(curr_guard).and_modify(|line| {
-                            for i in curr_time_start..minute {
-                                line[usize::from(i)] += 1
-                            }
-                        });
-                    }
-                    _ => {
-                        curr_guard = message.split_whitespace().nth(1).unwrap()[1..]
-                            .parse()
-                            .unwrap();
-
-                        guards.entry(curr_guard).or_insert([0; 60]);
-                    }
-                }
+enum Event {
+    BeginsShift(u16),
+    FallsAsleep(u8),
+    WakesUp(u8),
+}
 
-                (guards, curr_guard, curr_time_start)
-            },
-        )
-        .0
+fn parse_event(re: &Regex, guard_re: &Regex, i: usize, line: &str) -> Result<Event> {
+    let cap = re
+        .captures(line)
+        .ok_or_else(|| Error::parse(i, line, "expected \"[date hh:mm] message\""))?;
+    let minute: u8 = cap[1].parse().map_err(|err| Error::parse(i, line, err))?;
+
+    if minute >= 60 {
+        return Err(Error::parse(i, line, "minute out of range"));
+    }
+
+    match &cap[2] {
+        "falls asleep" => Ok(Event::FallsAsleep(minute)),
+        "wakes up" => Ok(Event::WakesUp(minute)),
+        message => Ok(Event::BeginsShift(
+            guard_re
+                .captures(message)
+                .ok_or_else(|| Error::parse(i, line, "unknown message"))?[1]
+                .parse()
+                .map_err(|err| Error::parse(i, line, err))?,
+        )),
+    }
 }
 
-fn part1(input: InputType) -> Int {
-    let mut guard_id = 0;
-    let mut max_total_sleep = 0;
-    let mut asleep_most_minute = 0;
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Input = InputType;
+    type Output1 = Int;
+    type Output2 = Int;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<InputType> {
+        let re = Regex::new(r"^\[.+ \d+:(\d+)\] (.+)$").unwrap();
+        let guard_re = Regex::new(r"^Guard #(\d+) begins shift$").unwrap();
+
+        let mut guards: InputType = HashMap::new();
+        let mut curr_guard = None;
+        let mut curr_time_start = 0;
+
+        // Records are in chronological order once sorted; keep the original line numbers for errors.
+        for (i, line) in input
+            .trim()
+            .lines()
+            .enumerate()
+            .sorted_unstable_by_key(|&(_, line)| line)
+        {
+            let Some(event) = parser.line(parse_event(&re, &guard_re, i, line)) else {
+                continue;
+            };
+
+            match event {
+                Event::FallsAsleep(minute) => curr_time_start = minute,
+                Event::WakesUp(minute) => {
+                    let Some(guard) = curr_guard else {
+                        parser.reject(Error::parse(i, line, "no guard is on shift"));
+                        continue;
+                    };
+
+                    guards.entry(guard).and_modify(|line| {
+                        for i in curr_time_start..minute {
+                            line[usize::from(i)] += 1
+                        }
+                    });
+                }
+                Event::BeginsShift(guard) => {
+                    curr_guard = Some(guard);
+                    guards.entry(guard).or_insert([0; 60]);
+                }
+            }
+        }
+
+        Ok(guards)
+    }
 
-    for (guard, sleep) in input {
-        let guard_total_sleep = sleep.iter().sum();
+    fn part1(input: InputType) -> Result<Int> {
+        let mut guard_id = 0;
+        let mut max_total_sleep = 0;
+        let mut asleep_most_minute = 0;
+
+        for (guard, sleep) in input {
+            let guard_total_sleep = sleep.iter().sum();
+
+            if guard_total_sleep > max_total_sleep {
+                guard_id = guard;
+                max_total_sleep = guard_total_sleep;
+                asleep_most_minute = peak_minute(&sleep);
+            }
+        }
 
-        if guard_total_sleep > max_total_sleep {
-            guard_id = guard;
-            max_total_sleep = guard_total_sleep;
-            asleep_most_minute = sleep.iter().position_max().unwrap();
+        if max_total_sleep == 0 {
+            return Err(Error::NoSolution("no guard ever falls asleep".to_string()));
         }
+
+        Ok(guard_id as Int * asleep_most_minute as Int)
     }
 
-    guard_id as Int * asleep_most_minute as Int
-}
+    fn part2(input: InputType) -> Result<Int> {
+        let mut guard_id = 0;
+        let mut peak_minute = 0;
+        let mut peak_minute_freq = 0;
 
-fn part2(input: InputType) -> Int {
-    let mut guard_id = 0;
-    let mut peak_minute = 0;
-    let mut peak_minute_freq = 0;
+        for (guard, sleep) in input {
+            let guard_peak_minute = self::peak_minute(&sleep);
 
-    for (guard, sleep) in input {
-        let guard_peak_minute = sleep.iter().position_max().unwrap();
+            if sleep[guard_peak_minute] > peak_minute_freq {
+                guard_id = guard;
+                peak_minute = guard_peak_minute;
+                peak_minute_freq = sleep[guard_peak_minute];
+            }
+        }
 
-        if sleep[guard_peak_minute] > peak_minute_freq {
-            guard_id = guard;
-            peak_minute = guard_peak_minute;
-            peak_minute_freq = sleep[guard_peak_minute];
+        if peak_minute_freq == 0 {
+            return Err(Error::NoSolution("no guard ever falls asleep".to_string()));
         }
+
+        Ok(guard_id as Int * peak_minute as Int)
     }
+}
 
-    guard_id as Int * peak_minute as Int
+/// The minute a guard is most often asleep.
+fn peak_minute(sleep: &[u16; 60]) -> usize {
+    sleep.iter().position_max().unwrap_or(0)
 }
 
 pub fn main() {
-    let input = read_input();
-
-    let mut now = Instant::now();
-    let part1 = part1(input.clone());
-    let part1_elapsed = now.elapsed();
-    now = Instant::now();
-    let part2 = part2(input);
-    let part2_elapsed = now.elapsed();
-
-    println!("--- Day 04 ---");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
-    println!("Part 1 took: {:.2?}", part1_elapsed);
-    println!("Part 2 took: {:.2?}", part2_elapsed);
-
-    assert_eq!(part1, 14346);
-    assert_eq!(part2, 5705);
+    run::<Day04>();
 }
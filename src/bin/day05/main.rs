@@ -1,30 +1,64 @@
-use std::fs;
-use std::time::Instant;
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
 
 type Int = usize;
 type InputType = Vec<char>;
 
-fn read_input() -> InputType {
-    fs::read_to_string("./src/bin/day05/input.txt")
-        .unwrap()
-        .trim()
-        .lines()
-        .flat_map(str::chars)
-        .collect()
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Input = InputType;
+    type Output1 = Int;
+    type Output2 = Int;
+
+    fn parse(input: &str, _parser: &mut Parser) -> Result<InputType> {
+        let mut polymer = vec![];
+
+        for (i, line) in input.trim().lines().enumerate() {
+            if let Some(unit) = line.chars().find(|c| !c.is_ascii_alphabetic()) {
+                return Err(Error::parse(i, line, format!("invalid unit {unit:?}")));
+            }
+
+            polymer.extend(line.chars());
+        }
+
+        Ok(polymer)
+    }
+
+    fn part1(input: InputType) -> Result<Int> {
+        Ok(react(&input))
+    }
+
+    fn part2(input: InputType) -> Result<Int> {
+        Ok("abcdefghijklmnopqrstuvwxyz"
+            .chars()
+            .fold(Int::MAX, |acc, curr| {
+                react(
+                    &input
+                        .iter()
+                        .filter(|c| !c.eq_ignore_ascii_case(&curr))
+                        .copied()
+                        .collect::<Vec<_>>(),
+                )
+                .min(acc)
+            }))
+    }
 }
 
-fn part1(input: InputType) -> Int {
+/// The length of `input` once every pair of opposite-polarity units has reacted.
+fn react(input: &[char]) -> Int {
     let mut answer: Vec<char> = Vec::with_capacity(input.len());
     let mut index_to_check = 0;
 
     while index_to_check < input.len() {
         let Some(lhs) = answer.last() else {
-            answer.push(*input.get(index_to_check).unwrap());
+            answer.push(input[index_to_check]);
             index_to_check += 1;
             continue;
         };
 
-        let rhs = input.get(index_to_check).unwrap();
+        let rhs = &input[index_to_check];
 
         if lhs != rhs && lhs.eq_ignore_ascii_case(rhs) {
             answer.pop();
@@ -39,37 +73,6 @@ fn part1(input: InputType) -> Int {
     answer.len()
 }
 
-fn part2(input: InputType) -> Int {
-    "abcdefghijklmnopqrstuvwxyz"
-        .chars()
-        .fold(Int::MAX, |acc, curr| {
-            part1(
-                input
-                    .iter()
-                    .filter(|c| !c.eq_ignore_ascii_case(&curr))
-                    .copied()
-                    .collect(),
-            )
-            .min(acc)
-        })
-}
-
 pub fn main() {
-    let input = read_input();
-
-    let mut now = Instant::now();
-    let part1 = part1(input.clone());
-    let part1_elapsed = now.elapsed();
-    now = Instant::now();
-    let part2 = part2(input);
-    let part2_elapsed = now.elapsed();
-
-    println!("--- Day 05 ---");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
-    println!("Part 1 took: {:.2?}", part1_elapsed);
-    println!("Part 2 took: {:.2?}", part2_elapsed);
-
-    assert_eq!(part1, 9704);
-    assert_eq!(part2, 6942);
+    run::<Day05>();
 }
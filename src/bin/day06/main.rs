@@ -1,117 +1,126 @@
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
-use std::fs;
-use std::time::Instant;
 
 use itertools::Itertools;
 
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
+
 type Int = i32;
 type InputType = (Vec<(Int, Int)>, Int);
 
-fn read_input() -> InputType {
-    let file = fs::read_to_string("./src/bin/day06/input.txt").unwrap();
+fn parse_coord(i: usize, line: &str) -> Result<(Int, Int)> {
+    let (x, y) = line
+        .split(',')
+        .map(|n| n.trim().parse::<Int>())
+        .collect_tuple()
+        .ok_or_else(|| Error::parse(i, line, "expected \"x, y\""))?;
+    let (x, y) = (
+        x.map_err(|err| Error::parse(i, line, err))?,
+        y.map_err(|err| Error::parse(i, line, err))?,
+    );
+
+    if x < 0 || y < 0 {
+        return Err(Error::parse(i, line, "coordinates must not be negative"));
+    }
 
-    let mut coords = vec![];
-    let mut max = 0;
+    Ok((x, y))
+}
 
-    for line in file.trim().lines() {
-        let Some((x, y)) = line
-            .split(',')
-            .flat_map(|n| n.trim().parse::<Int>())
-            .collect_tuple()
-        else {
-            continue;
-        };
+pub struct Day06;
 
-        max = max.max(x).max(y);
-        coords.push((x, y))
-    }
+impl Solution for Day06 {
+    const DAY: u8 = 6;
 
-    (coords, max)
-}
+    type Input = InputType;
+    type Output1 = Int;
+    type Output2 = Int;
 
-fn part1((input, max): InputType) -> i32 {
-    let mut counts = HashMap::new();
-    let mut excluded = HashSet::new();
-
-    for xx in 0..max {
-        for yy in 0..max {
-            let (min_coord, _) =
-                input
-                    .iter()
-                    .fold((None, Int::MAX), |(min_coord, min_dist), (x, y)| {
-                        let dist = (xx as Int - x).abs() + (yy as Int - y).abs();
-
-                        match dist.cmp(&min_dist) {
-                            Ordering::Less => (Some((x, y)), dist),
-                            Ordering::Equal => (None, dist),
-                            Ordering::Greater => (min_coord, min_dist),
-                        }
-                    });
-
-            let Some(min_coord) = min_coord else {
+    fn parse(input: &str, parser: &mut Parser) -> Result<InputType> {
+        let mut coords = vec![];
+        let mut max = 0;
+
+        for (i, line) in input.trim().lines().enumerate() {
+            let Some((x, y)) = parser.line(parse_coord(i, line)) else {
                 continue;
             };
 
-            if xx == 0 || xx == max - 1 || yy == 0 || yy == max - 1 {
-                excluded.insert(min_coord);
-            }
-
-            counts
-                .entry(min_coord)
-                .and_modify(|acc| *acc += 1)
-                .or_insert(1);
+            max = max.max(x).max(y);
+            coords.push((x, y))
         }
+
+        Ok((coords, max))
     }
 
-    *counts
-        .iter()
-        .filter(|(key, _)| !excluded.contains(key))
-        .max_by(|(_, dist1), (_, dist2)| dist1.cmp(dist2))
-        .map(|(_, dist)| dist)
-        .unwrap()
-}
+    fn part1((input, max): InputType) -> Result<Int> {
+        let mut counts = HashMap::new();
+        let mut excluded = HashSet::new();
+
+        for xx in 0..=max {
+            for yy in 0..=max {
+                let (min_coord, _) =
+                    input
+                        .iter()
+                        .fold((None, Int::MAX), |(min_coord, min_dist), (x, y)| {
+                            let dist = (xx as Int - x).abs() + (yy as Int - y).abs();
+
+                            match dist.cmp(&min_dist) {
+                                Ordering::Less => (Some((x, y)), dist),
+                                Ordering::Equal => (None, dist),
+                                Ordering::Greater => (min_coord, min_dist),
+                            }
+                        });
+
+                let Some(min_coord) = min_coord else {
+                    continue;
+                };
+
+                if xx == 0 || xx == max || yy == 0 || yy == max {
+                    excluded.insert(min_coord);
+                }
+
+                counts
+                    .entry(min_coord)
+                    .and_modify(|acc| *acc += 1)
+                    .or_insert(1);
+            }
+        }
 
-fn part2((input, max): InputType) -> Int {
-    let max = max as usize;
-    let mut x_distances = vec![0; max];
-    let mut y_distances = vec![0; max];
+        counts
+            .iter()
+            .filter(|(key, _)| !excluded.contains(key))
+            .max_by(|(_, dist1), (_, dist2)| dist1.cmp(dist2))
+            .map(|(_, dist)| *dist)
+            .ok_or_else(|| Error::NoSolution("every area is infinite".to_string()))
+    }
 
-    for (x, y) in input {
-        for i in 0..max {
-            *x_distances.get_mut(i).unwrap() += (i as Int - x).abs();
-            *y_distances.get_mut(i).unwrap() += (i as Int - y).abs();
+    fn part2((input, max): InputType) -> Result<Int> {
+        // Distances for each column and row in 0..=max.
+        let size = max as usize + 1;
+        let mut x_distances = vec![0; size];
+        let mut y_distances = vec![0; size];
+
+        for (x, y) in input {
+            for (i, (x_distance, y_distance)) in
+                x_distances.iter_mut().zip(&mut y_distances).enumerate()
+            {
+                *x_distance += (i as Int - x).abs();
+                *y_distance += (i as Int - y).abs();
+            }
         }
-    }
 
-    let mut count = 0;
-    for x in x_distances {
-        for y in y_distances.clone() {
-            if x + y < 10_000 {
-                count += 1;
+        let mut count = 0;
+        for x in x_distances {
+            for y in y_distances.clone() {
+                if x + y < 10_000 {
+                    count += 1;
+                }
             }
         }
-    }
 
-    count
+        Ok(count)
+    }
 }
 
 pub fn main() {
-    let input = read_input();
-
-    let mut now = Instant::now();
-    let part1 = part1(input.clone());
-    let part1_elapsed = now.elapsed();
-    now = Instant::now();
-    let part2 = part2(input);
-    let part2_elapsed = now.elapsed();
-
-    println!("--- Day 06 ---");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
-    println!("Part 1 took: {:.2?}", part1_elapsed);
-    println!("Part 2 took: {:.2?}", part2_elapsed);
-
-    assert_eq!(part1, 4166);
-    assert_eq!(part2, 42250);
+    run::<Day06>();
 }
@@ -1,6 +1,6 @@
 use std::collections::{BTreeMap, VecDeque};
-use std::fs;
-use std::time::Instant;
+
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
 
 type Int = usize;
 type InputType = VecDeque<Int>;
@@ -11,41 +11,41 @@ struct Node {
     metadata: Vec<Int>,
 }
 
-fn read_input() -> InputType {
-    fs::read_to_string("./src/bin/day08/input.txt")
-        .unwrap()
-        .split_whitespace()
-        .flat_map(|s| s.parse::<Int>())
-        .collect()
+fn truncated() -> Error {
+    Error::Invalid("the tree ends in the middle of a node".to_string())
+}
+
+fn take_metadata(input: &mut InputType, n_metadata: Int) -> Result<Vec<Int>> {
+    if input.len() < n_metadata {
+        return Err(truncated());
+    }
+
+    Ok(input.drain(0..n_metadata).collect())
 }
 
 fn process(
     input: &mut InputType,
     mut tree: BTreeMap<Int, Node>,
     curr_idx: Int,
-) -> BTreeMap<Int, Node> {
-    let Some(n_nodes) = input.pop_front() else {
-        return tree;
-    };
-    let Some(n_metadata) = input.pop_front() else {
-        return tree;
-    };
+) -> Result<BTreeMap<Int, Node>> {
+    let n_nodes = input.pop_front().ok_or_else(truncated)?;
+    let n_metadata = input.pop_front().ok_or_else(truncated)?;
 
     if n_nodes == 0 {
-        return BTreeMap::from([(
+        return Ok(BTreeMap::from([(
             curr_idx,
             Node {
                 children: Vec::new(),
-                metadata: input.drain(0..n_metadata).collect(),
+                metadata: take_metadata(input, n_metadata)?,
             },
-        )]);
+        )]));
     }
 
     let mut children_idx = vec![];
 
     for _ in 0..n_nodes {
         let child_idx = curr_idx + 1 + tree.len();
-        let result = process(input, BTreeMap::new(), child_idx);
+        let result = process(input, BTreeMap::new(), child_idx)?;
 
         tree.extend(result);
         children_idx.push(child_idx);
@@ -55,24 +55,29 @@ fn process(
         curr_idx,
         Node {
             children: children_idx,
-            metadata: input.drain(0..n_metadata).collect(),
+            metadata: take_metadata(input, n_metadata)?,
         },
     );
 
-    tree
+    Ok(tree)
 }
 
-fn part1(mut input: InputType) -> Int {
-    let tree = process(&mut input, BTreeMap::new(), 0);
+/// Builds the tree rooted at index 0, which must use up the whole input.
+fn build_tree(mut input: InputType) -> Result<BTreeMap<Int, Node>> {
+    let tree = process(&mut input, BTreeMap::new(), 0)?;
 
-    tree.iter()
-        .fold(0, |acc, curr| acc + curr.1.metadata.iter().sum::<Int>())
+    if !input.is_empty() {
+        return Err(Error::Invalid(format!(
+            "{} numbers left over after the root node",
+            input.len()
+        )));
+    }
+
+    Ok(tree)
 }
 
 fn calc_part2(tree: &BTreeMap<Int, Node>, curr_idx: &Int) -> Int {
-    let Some(Node { children, metadata }) = tree.get(curr_idx) else {
-        panic!();
-    };
+    let Node { children, metadata } = &tree[curr_idx];
 
     if children.is_empty() {
         return metadata.iter().sum();
@@ -80,33 +85,52 @@ fn calc_part2(tree: &BTreeMap<Int, Node>, curr_idx: &Int) -> Int {
 
     metadata
         .iter()
-        .flat_map(|i| children.get(i - 1))
+        .flat_map(|i| children.get(i.checked_sub(1)?))
         .map(|i| calc_part2(tree, i))
         .sum()
 }
 
-fn part2(mut input: InputType) -> Int {
-    let tree = process(&mut input, BTreeMap::new(), 0);
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+
+    type Input = InputType;
+    type Output1 = Int;
+    type Output2 = Int;
 
-    calc_part2(&tree, &0)
+    /// The tree is one flat run of numbers, so leaving out a bad one would shift every header
+    /// and metadata entry after it: a bad number is an error even in lenient mode.
+    fn parse(input: &str, _parser: &mut Parser) -> Result<InputType> {
+        let mut numbers = VecDeque::new();
+
+        for (i, line) in input.lines().enumerate() {
+            for s in line.split_whitespace() {
+                numbers.push_back(
+                    s.parse::<Int>()
+                        .map_err(|err| Error::parse(i, line, format!("{s}: {err}")))?,
+                );
+            }
+        }
+
+        Ok(numbers)
+    }
+
+    fn part1(input: InputType) -> Result<Int> {
+        let tree = build_tree(input)?;
+
+        Ok(tree
+            .iter()
+            .fold(0, |acc, curr| acc + curr.1.metadata.iter().sum::<Int>()))
+    }
+
+    fn part2(input: InputType) -> Result<Int> {
+        let tree = build_tree(input)?;
+
+        Ok(calc_part2(&tree, &0))
+    }
 }
 
 pub fn main() {
-    let input = read_input();
-
-    let mut now = Instant::now();
-    let part1 = part1(input.clone());
-    let part1_elapsed = now.elapsed();
-    now = Instant::now();
-    let part2 = part2(input);
-    let part2_elapsed = now.elapsed();
-
-    println!("--- Day 08 ---");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
-    println!("Part 1 took: {:.2?}", part1_elapsed);
-    println!("Part 2 took: {:.2?}", part2_elapsed);
-
-    assert_eq!(part1, 36566);
-    assert_eq!(part2, 30548);
+    run::<Day08>();
 }
@@ -1,24 +1,10 @@
 use std::collections::VecDeque;
-use std::fs;
-use std::time::Instant;
+
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
 
 type Int = usize;
 type InputType = (Int, Int);
 
-fn read_input() -> InputType {
-    fs::read_to_string("./src/bin/day09/input.txt")
-        .unwrap()
-        .trim()
-        .lines()
-        .flat_map(|s| -> Option<(Int, Int)> {
-            let mut words = s.split_whitespace();
-
-            Some((words.next()?.parse().ok()?, words.nth(5)?.parse().ok()?))
-        })
-        .next()
-        .unwrap()
-}
-
 fn play((n_players, n_marbles): InputType) -> Int {
     let mut players = vec![Vec::<Int>::new(); n_players];
     let mut circle = VecDeque::<Int>::with_capacity(n_marbles);
@@ -29,10 +15,10 @@ fn play((n_players, n_marbles): InputType) -> Int {
         if round % 23 == 0 {
             circle.rotate_right(7);
 
-            let curr_player = players.get_mut(round % n_players).unwrap();
+            let curr_player = &mut players[round % n_players];
 
             curr_player.push(round);
-            curr_player.push(circle.pop_front().unwrap());
+            curr_player.extend(circle.pop_front());
 
             continue;
         }
@@ -45,32 +31,56 @@ fn play((n_players, n_marbles): InputType) -> Int {
         .iter()
         .map(|marbles| marbles.iter().sum())
         .max()
-        .unwrap()
-}
-fn part1(input: InputType) -> Int {
-    play(input)
+        .unwrap_or(0)
 }
 
-fn part2(input: InputType) -> Int {
-    play((input.0, input.1 * 100))
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+
+    type Input = InputType;
+    type Output1 = Int;
+    type Output2 = Int;
+
+    fn parse(input: &str, _parser: &mut Parser) -> Result<InputType> {
+        let line = input.trim();
+        let format = || {
+            Error::parse(
+                0,
+                line,
+                "expected \"N players; last marble is worth M points\"",
+            )
+        };
+
+        let mut words = line.split_whitespace();
+        let n_players: Int = words
+            .next()
+            .ok_or_else(format)?
+            .parse()
+            .map_err(|err| Error::parse(0, line, err))?;
+        let n_marbles = words
+            .nth(5)
+            .ok_or_else(format)?
+            .parse()
+            .map_err(|err| Error::parse(0, line, err))?;
+
+        if n_players == 0 {
+            return Err(Error::parse(0, line, "there must be at least one player"));
+        }
+
+        Ok((n_players, n_marbles))
+    }
+
+    fn part1(input: InputType) -> Result<Int> {
+        Ok(play(input))
+    }
+
+    fn part2(input: InputType) -> Result<Int> {
+        Ok(play((input.0, input.1 * 100)))
+    }
 }
 
 pub fn main() {
-    let input = read_input();
-
-    let mut now = Instant::now();
-    let part1 = part1(input);
-    let part1_elapsed = now.elapsed();
-    now = Instant::now();
-    let part2 = part2(input);
-    let part2_elapsed = now.elapsed();
-
-    println!("--- Day 09 ---");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
-    println!("Part 1 took: {:.2?}", part1_elapsed);
-    println!("Part 2 took: {:.2?}", part2_elapsed);
-
-    assert_eq!(part1, 371284);
-    assert_eq!(part2, 3038972494);
+    run::<Day09>();
 }
@@ -1,100 +1,123 @@
 use itertools::Itertools;
-use itertools::MinMaxResult::MinMax;
 use regex::Regex;
-use std::fs;
-use std::time::Instant;
+
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
 
 type Int = i32;
 type Point = (Int, Int, Int, Int);
 type InputType = Vec<Point>;
 
-fn read_input() -> InputType {
-    let re = Regex::new(r"^position=<(.+),(.+)> velocity=<(.+),(.+)>$").unwrap();
-
-    fs::read_to_string("./src/bin/day10/input.txt")
-        .unwrap()
-        .trim()
-        .lines()
-        .flat_map(|line| re.captures(line))
-        .flat_map(|cap| {
-            cap.iter()
-                .skip(1)
-                .take(4)
-                .flat_map(|s| s?.as_str().trim().parse::<Int>().ok())
-                .collect_tuple::<Point>()
-        })
-        .collect()
-}
+/// Moves the points until the message appears (the sky is less than 10 rows tall) and returns
+/// the time it took with the points at that time.
+fn converge(input: &InputType) -> Result<(Int, Vec<(Int, Int)>)> {
+    let mut prev_height = Int::MAX;
 
-fn part1(input: InputType) -> Int {
     for i in 0.. {
         let new_points: Vec<(Int, Int)> = input
             .iter()
             .map(|(x, y, dx, dy)| (x + dx * i, y + dy * i))
             .collect();
 
-        let MinMax(min_x, max_x) = new_points.iter().map(|point| point.0).minmax() else {
-            panic!();
-        };
-        let MinMax(min_y, max_y) = new_points.iter().map(|point| point.1).minmax() else {
-            panic!();
-        };
-
-        if max_y - min_y < 10 {
-            for y in min_y..=max_y {
-                'outer: for x in min_x..=max_x {
-                    for &(xx, yy) in &new_points {
-                        if x == xx && y == yy {
-                            print!("#");
-                            continue 'outer;
-                        }
-                    }
-                    print!(".");
-                }
-                println!();
-            }
-            break;
+        let (min_y, max_y) = new_points
+            .iter()
+            .map(|point| point.1)
+            .minmax()
+            .into_option()
+            .ok_or_else(|| Error::NoSolution("there are no points".to_string()))?;
+
+        let height = max_y - min_y;
+
+        if height < 10 {
+            return Ok((i, new_points));
         }
+
+        // The height is a convex function of time, so once it stops shrinking it never will.
+        if height >= prev_height {
+            return Err(Error::NoSolution(
+                "the points drift apart without forming a message".to_string(),
+            ));
+        }
+
+        prev_height = height;
     }
 
-    0
+    unreachable!()
 }
 
-fn part2(input: InputType) -> Int {
-    for i in 0.. {
-        let new_points: Vec<(Int, Int)> = input
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = InputType;
+    type Output1 = String;
+    type Output2 = Int;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<InputType> {
+        let re = Regex::new(r"^position=<(.+),(.+)> velocity=<(.+),(.+)>$").unwrap();
+
+        Ok(input
+            .trim()
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                let cap = re.captures(line).ok_or_else(|| {
+                    Error::parse(i, line, "expected \"position=<x, y> velocity=<dx, dy>\"")
+                })?;
+                let number = |group: usize| {
+                    cap[group]
+                        .trim()
+                        .parse()
+                        .map_err(|err| Error::parse(i, line, err))
+                };
+
+                Ok((number(1)?, number(2)?, number(3)?, number(4)?))
+            })
+            .filter_map(|point| parser.line(point))
+            .collect())
+    }
+
+    /// The message has to be read by eye, so the answer is the rendered sky.
+    fn part1(input: InputType) -> Result<String> {
+        let (_, new_points) = converge(&input)?;
+        let mut sky = String::new();
+
+        // `converge` only returns once there is at least one point.
+        let (min_x, max_x) = new_points
             .iter()
-            .map(|(x, y, dx, dy)| (x + dx * i, y + dy * i))
-            .collect();
+            .map(|point| point.0)
+            .minmax()
+            .into_option()
+            .unwrap();
+        let (min_y, max_y) = new_points
+            .iter()
+            .map(|point| point.1)
+            .minmax()
+            .into_option()
+            .unwrap();
 
-        let MinMax(min_y, max_y) = new_points.iter().map(|point| point.1).minmax() else {
-            panic!();
-        };
+        for y in min_y..=max_y {
+            sky.push('\n');
 
-        if max_y - min_y < 10 {
-            return i;
+            'outer: for x in min_x..=max_x {
+                for &(xx, yy) in &new_points {
+                    if x == xx && y == yy {
+                        sky.push('#');
+                        continue 'outer;
+                    }
+                }
+                sky.push('.');
+            }
         }
+
+        Ok(sky)
     }
 
-    panic!();
+    fn part2(input: InputType) -> Result<Int> {
+        Ok(converge(&input)?.0)
+    }
 }
 
 pub fn main() {
-    let input = read_input();
-
-    let mut now = Instant::now();
-    let part1 = part1(input.clone());
-    let part1_elapsed = now.elapsed();
-    now = Instant::now();
-    let part2 = part2(input);
-    let part2_elapsed = now.elapsed();
-
-    println!("--- Day 10 ---");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
-    println!("Part 1 took: {:.2?}", part1_elapsed);
-    println!("Part 2 took: {:.2?}", part2_elapsed);
-
-    // assert_eq!(part1, 0);
-    assert_eq!(part2, 10101);
+    run::<Day10>();
 }
@@ -1,33 +1,8 @@
-use std::fs;
-use std::time::Instant;
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
 
 type Int = i64;
 const N: usize = 300;
-type InputType = [[Int; N]; N];
-
-fn read_input() -> InputType {
-    let serial: Int = fs::read_to_string("./src/bin/day11/input.txt")
-        .unwrap()
-        .trim()
-        .parse()
-        .unwrap();
-
-    let mut grid = [[0; N]; N];
-
-    for (x, col) in grid.iter_mut().enumerate() {
-        for (y, cell) in col.iter_mut().enumerate() {
-            let rack_id = (x as Int + 1) + 10;
-            *cell = rack_id * (y as Int + 1);
-            *cell += serial;
-            *cell *= rack_id;
-            *cell /= 100;
-            *cell %= 10;
-            *cell -= 5;
-        }
-    }
-
-    grid
-}
+type InputType = Vec<[Int; N]>;
 
 fn do_sums(input: &InputType, part1: bool) -> (usize, usize, usize) {
     let mut max_value = Int::MIN;
@@ -35,7 +10,7 @@ fn do_sums(input: &InputType, part1: bool) -> (usize, usize, usize) {
     let mut max_y = 0;
     let mut max_z = 0;
 
-    let mut sums = *input;
+    let mut sums = input.clone();
 
     for z in 1..=N {
         if part1 && z > 3 {
@@ -49,8 +24,8 @@ fn do_sums(input: &InputType, part1: bool) -> (usize, usize, usize) {
                         continue;
                     }
 
-                    for zz in 0..z {
-                        sums[0][0] += input[zz][z - 1];
+                    for (zz, row) in input.iter().enumerate().take(z) {
+                        sums[0][0] += row[z - 1];
                         sums[0][0] += input[z - 1][zz];
                     }
 
@@ -94,34 +69,49 @@ fn do_sums(input: &InputType, part1: bool) -> (usize, usize, usize) {
     (max_x, max_y, max_z)
 }
 
-fn part1(input: InputType) -> (usize, usize) {
-    let (x, y, _) = do_sums(&input, true);
+pub struct Day11;
 
-    (x + 1, y + 1)
-}
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = InputType;
+    type Output1 = String;
+    type Output2 = String;
+
+    fn parse(input: &str, _parser: &mut Parser) -> Result<InputType> {
+        let line = input.trim();
+        let serial: Int = line.parse().map_err(|err| Error::parse(0, line, err))?;
+
+        let mut grid = vec![[0; N]; N];
 
-fn part2(input: InputType) -> (usize, usize, usize) {
-    let (x, y, z) = do_sums(&input, false);
+        for (x, col) in grid.iter_mut().enumerate() {
+            for (y, cell) in col.iter_mut().enumerate() {
+                let rack_id = (x as Int + 1) + 10;
+                *cell = rack_id * (y as Int + 1);
+                *cell += serial;
+                *cell *= rack_id;
+                *cell /= 100;
+                *cell %= 10;
+                *cell -= 5;
+            }
+        }
+
+        Ok(grid)
+    }
+
+    fn part1(input: InputType) -> Result<String> {
+        let (x, y, _) = do_sums(&input, true);
 
-    (x + 1, y + 1, z)
+        Ok(format!("{},{}", x + 1, y + 1))
+    }
+
+    fn part2(input: InputType) -> Result<String> {
+        let (x, y, z) = do_sums(&input, false);
+
+        Ok(format!("{},{},{}", x + 1, y + 1, z))
+    }
 }
 
 pub fn main() {
-    let input = read_input();
-
-    let mut now = Instant::now();
-    let part1 = part1(input);
-    let part1_elapsed = now.elapsed();
-    now = Instant::now();
-    let part2 = part2(input);
-    let part2_elapsed = now.elapsed();
-
-    println!("--- Day 11 ---");
-    println!("Part 1: {:?}", part1);
-    println!("Part 2: {:?}", part2);
-    println!("Part 1 took: {:.2?}", part1_elapsed);
-    println!("Part 2 took: {:.2?}", part2_elapsed);
-
-    assert_eq!(part1, (243, 34));
-    assert_eq!(part2, (90, 214, 15));
+    run::<Day11>();
 }
@@ -1,97 +1,99 @@
-use std::fs;
-use std::time::Instant;
-
 use itertools::Itertools;
 
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
+
 type Int = i64;
 type InputType = (Vec<bool>, [bool; NUM_MASKS]);
 
 const NUM_MASKS: usize = 2usize.pow(5);
 
-fn read_input() -> InputType {
-    let file = fs::read_to_string("./src/bin/day12/input.txt").unwrap();
-    let mut lines = file.trim().lines();
+pub struct Day12;
 
-    let state = lines
-        .next()
-        .and_then(|line| line.split_whitespace().nth_back(0))
-        .unwrap()
-        .chars()
-        .map(|c| c == '#')
-        .collect_vec();
+impl Solution for Day12 {
+    const DAY: u8 = 12;
 
-    let mut masks = [false; NUM_MASKS];
+    type Input = InputType;
+    type Output1 = Int;
+    type Output2 = Int;
 
-    for mask in lines.skip(1) {
-        let (criteria, _, result) = mask
-            .split_whitespace()
-            .collect_tuple::<(&str, &str, &str)>()
-            .unwrap();
+    fn parse(input: &str, parser: &mut Parser) -> Result<InputType> {
+        let mut lines = input.trim().lines().enumerate();
+        let is_pots = |s: &str| s.chars().all(|c| c == '#' || c == '.');
 
-        let idx = criteria
+        let (i, line) = lines
+            .next()
+            .ok_or_else(|| Error::Invalid("the input is empty".to_string()))?;
+        let state = line
+            .strip_prefix("initial state: ")
+            .filter(|state| is_pots(state))
+            .ok_or_else(|| Error::parse(i, line, "expected \"initial state: #..#\""))?
             .chars()
-            .fold(0, |acc, c| (acc << 1) + usize::from(c == '#'));
+            .map(|c| c == '#')
+            .collect_vec();
+
+        let mut masks = [false; NUM_MASKS];
+
+        for (i, line) in lines.skip(1) {
+            let rule = line
+                .split_once(" => ")
+                .filter(|(criteria, result)| {
+                    criteria.len() == 5 && result.len() == 1 && is_pots(criteria) && is_pots(result)
+                })
+                .ok_or_else(|| Error::parse(i, line, "expected \"..#.. => #\""));
+
+            let Some((criteria, result)) = parser.line(rule) else {
+                continue;
+            };
+
+            let idx = criteria
+                .chars()
+                .fold(0, |acc, c| (acc << 1) + usize::from(c == '#'));
+
+            masks[idx] = result == "#";
+        }
 
-        masks[idx] = result == "#";
+        Ok((state, masks))
     }
 
-    (state, masks)
-}
+    fn part1((mut state, masks): InputType) -> Result<Int> {
+        let mut new_state = Vec::with_capacity(state.len());
 
-fn part1((mut state, masks): InputType) -> Int {
-    let mut new_state = Vec::with_capacity(state.len());
+        const N: Int = 20;
 
-    const N: Int = 20;
+        for _ in 0..N {
+            let state_iter = [false; 4].iter().chain(state.iter()).chain([&false; 4]);
 
-    for _ in 0..N {
-        let state_iter = [false; 4].iter().chain(state.iter()).chain([&false; 4]);
+            for window in state_iter.tuple_windows::<(_, _, _, _, _)>() {
+                let idx = 16 * usize::from(*window.0)
+                    + 8 * usize::from(*window.1)
+                    + 4 * usize::from(*window.2)
+                    + 2 * usize::from(*window.3)
+                    + usize::from(*window.4);
 
-        for window in state_iter.tuple_windows::<(_, _, _, _, _)>() {
-            let idx = 16 * usize::from(*window.0)
-                + 8 * usize::from(*window.1)
-                + 4 * usize::from(*window.2)
-                + 2 * usize::from(*window.3)
-                + usize::from(*window.4);
+                new_state.push(masks[idx]);
+            }
 
-            new_state.push(masks[idx]);
+            state = new_state.clone();
+            new_state.clear();
         }
 
-        state = new_state.clone();
-        new_state.clear();
+        Ok(state.iter().enumerate().fold(
+            0,
+            |acc, (i, curr)| {
+                if *curr {
+                    acc + i as Int - N * 2
+                } else {
+                    acc
+                }
+            },
+        ))
     }
 
-    state.iter().enumerate().fold(
-        0,
-        |acc, (i, curr)| {
-            if *curr {
-                acc + i as Int - N * 2
-            } else {
-                acc
-            }
-        },
-    )
-}
-
-fn part2(_input: InputType) -> Int {
-    0
+    fn part2(_input: InputType) -> Result<Int> {
+        Err(Error::Unsolved)
+    }
 }
 
 pub fn main() {
-    let input = read_input();
-
-    let mut now = Instant::now();
-    let part1 = part1(input.clone());
-    let part1_elapsed = now.elapsed();
-    now = Instant::now();
-    let part2 = part2(input);
-    let part2_elapsed = now.elapsed();
-
-    println!("--- Day 12 ---");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
-    println!("Part 1 took: {:.2?}", part1_elapsed);
-    println!("Part 2 took: {:.2?}", part2_elapsed);
-
-    assert_eq!(part1, 3605);
-    // assert_eq!(part2, 0);
+    run::<Day12>();
 }
@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
-use std::fs;
 use std::mem::take;
-use std::time::Instant;
+
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
 
 type Int = usize;
 type InputType = (BTreeMap<Coord, Cart>, [[Option<RailSegment>; N]; N]);
@@ -11,7 +11,7 @@ const N: usize = 150;
 type Coord = (Int, Int);
 
 #[derive(Debug, Copy, Clone, PartialEq)]
-enum RailSegment {
+pub enum RailSegment {
     Curve,
     BackCurve,
     Vertical,
@@ -20,7 +20,7 @@ enum RailSegment {
 }
 
 #[derive(Copy, Clone)]
-enum CartDirection {
+pub enum CartDirection {
     Up,
     Right,
     Down,
@@ -28,7 +28,7 @@ enum CartDirection {
 }
 
 #[derive(Copy, Clone)]
-enum TurnDirection {
+pub enum TurnDirection {
     Left,
     Straight,
     Right,
@@ -61,82 +61,17 @@ impl CartDirection {
 }
 
 #[derive(Copy, Clone)]
-struct Cart {
+pub struct Cart {
     direction: CartDirection,
     next_turn: TurnDirection,
 }
 
-fn read_input() -> InputType {
-    let mut carts = BTreeMap::<Coord, Cart>::new();
-
-    let mut rails = [[None; N]; N];
-
-    let file = fs::read_to_string("./src/bin/day13/input.txt").unwrap();
-
-    for (y, line) in file.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            rails[x][y] = match c {
-                '|' => Some(RailSegment::Vertical),
-                '-' => Some(RailSegment::Horizontal),
-                '/' => Some(RailSegment::Curve),
-                '\\' => Some(RailSegment::BackCurve),
-                '+' => Some(RailSegment::Intersection),
-
-                '^' => {
-                    carts.insert(
-                        (y, x),
-                        Cart {
-                            direction: CartDirection::Up,
-                            next_turn: TurnDirection::Left,
-                        },
-                    );
-                    Some(RailSegment::Vertical)
-                }
-                '>' => {
-                    carts.insert(
-                        (y, x),
-                        Cart {
-                            direction: CartDirection::Right,
-                            next_turn: TurnDirection::Left,
-                        },
-                    );
-                    Some(RailSegment::Horizontal)
-                }
-                'v' => {
-                    carts.insert(
-                        (y, x),
-                        Cart {
-                            direction: CartDirection::Down,
-                            next_turn: TurnDirection::Left,
-                        },
-                    );
-                    Some(RailSegment::Vertical)
-                }
-                '<' => {
-                    carts.insert(
-                        (y, x),
-                        Cart {
-                            direction: CartDirection::Left,
-                            next_turn: TurnDirection::Left,
-                        },
-                    );
-                    Some(RailSegment::Horizontal)
-                }
-
-                _ => None,
-            }
-        }
-    }
-
-    (carts, rails)
-}
-
-fn get_new_coord(y: usize, x: usize, direction: CartDirection) -> (usize, usize) {
+fn get_new_coord(y: usize, x: usize, direction: CartDirection) -> Option<(usize, usize)> {
     match direction {
-        CartDirection::Up => (y - 1, x),
-        CartDirection::Right => (y, x + 1),
-        CartDirection::Down => (y + 1, x),
-        CartDirection::Left => (y, x - 1),
+        CartDirection::Up => Some((y.checked_sub(1)?, x)),
+        CartDirection::Right => Some((y, x + 1)),
+        CartDirection::Down => Some((y + 1, x)),
+        CartDirection::Left => Some((y, x.checked_sub(1)?)),
     }
 }
 
@@ -165,23 +100,26 @@ fn get_resulting_direction(
     }
 }
 
-fn simulate((mut carts, rails): InputType, part1: bool) -> (usize, usize) {
+fn simulate((mut carts, rails): InputType, part1: bool) -> Result<(usize, usize)> {
     let mut new_carts = BTreeMap::new();
 
     while carts.len() > 1 {
         while let Some(((y, x), cart)) = carts.pop_first() {
-            let new_coord = get_new_coord(y, x, cart.direction);
+            let off_track = || Error::Invalid(format!("the cart at {x},{y} runs off the track"));
+            let new_coord = get_new_coord(y, x, cart.direction).ok_or_else(off_track)?;
 
             if carts.remove(&new_coord).is_some() || new_carts.remove(&new_coord).is_some() {
                 if part1 {
-                    return (new_coord.1, new_coord.0);
+                    return Ok((new_coord.1, new_coord.0));
                 }
 
                 continue;
             }
 
-            let rail_segment =
-                rails[new_coord.1][new_coord.0].expect("cart should always move to a rail segment");
+            let rail_segment = rails
+                .get(new_coord.1)
+                .and_then(|col| col.get(new_coord.0).copied().flatten())
+                .ok_or_else(off_track)?;
 
             let direction = get_resulting_direction(cart, rail_segment);
             let next_turn = if rail_segment == RailSegment::Intersection {
@@ -202,35 +140,109 @@ fn simulate((mut carts, rails): InputType, part1: bool) -> (usize, usize) {
         carts = take(&mut new_carts);
     }
 
-    let ((y, x), _) = carts.pop_first().expect("there should be one last cart");
+    if part1 {
+        return Err(Error::NoSolution("no two carts ever collide".to_string()));
+    }
 
-    (x, y)
-}
+    let ((y, x), _) = carts
+        .pop_first()
+        .ok_or_else(|| Error::NoSolution("every cart crashes".to_string()))?;
 
-fn part1(input: InputType) -> (Int, Int) {
-    simulate(input, true)
+    Ok((x, y))
 }
 
-fn part2(input: InputType) -> (Int, Int) {
-    simulate(input, false)
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = InputType;
+    type Output1 = String;
+    type Output2 = String;
+
+    fn parse(input: &str, _parser: &mut Parser) -> Result<InputType> {
+        let mut carts = BTreeMap::<Coord, Cart>::new();
+
+        let mut rails = [[None; N]; N];
+
+        for (y, line) in input.lines().enumerate() {
+            if y >= N || line.chars().count() > N {
+                return Err(Error::OutOfRange(format!(
+                    "tracks larger than {N}x{N} are not supported"
+                )));
+            }
+
+            for (x, c) in line.chars().enumerate() {
+                rails[x][y] = match c {
+                    '|' => Some(RailSegment::Vertical),
+                    '-' => Some(RailSegment::Horizontal),
+                    '/' => Some(RailSegment::Curve),
+                    '\\' => Some(RailSegment::BackCurve),
+                    '+' => Some(RailSegment::Intersection),
+
+                    '^' => {
+                        carts.insert(
+                            (y, x),
+                            Cart {
+                                direction: CartDirection::Up,
+                                next_turn: TurnDirection::Left,
+                            },
+                        );
+                        Some(RailSegment::Vertical)
+                    }
+                    '>' => {
+                        carts.insert(
+                            (y, x),
+                            Cart {
+                                direction: CartDirection::Right,
+                                next_turn: TurnDirection::Left,
+                            },
+                        );
+                        Some(RailSegment::Horizontal)
+                    }
+                    'v' => {
+                        carts.insert(
+                            (y, x),
+                            Cart {
+                                direction: CartDirection::Down,
+                                next_turn: TurnDirection::Left,
+                            },
+                        );
+                        Some(RailSegment::Vertical)
+                    }
+                    '<' => {
+                        carts.insert(
+                            (y, x),
+                            Cart {
+                                direction: CartDirection::Left,
+                                next_turn: TurnDirection::Left,
+                            },
+                        );
+                        Some(RailSegment::Horizontal)
+                    }
+
+                    ' ' => None,
+                    _ => return Err(Error::parse(y, line, format!("unknown track piece {c:?}"))),
+                }
+            }
+        }
+
+        Ok((carts, rails))
+    }
+
+    fn part1(input: InputType) -> Result<String> {
+        let (x, y) = simulate(input, true)?;
+
+        Ok(format!("{x},{y}"))
+    }
+
+    fn part2(input: InputType) -> Result<String> {
+        let (x, y) = simulate(input, false)?;
+
+        Ok(format!("{x},{y}"))
+    }
 }
 
 pub fn main() {
-    let input = read_input();
-
-    let mut now = Instant::now();
-    let part1 = part1(input.clone());
-    let part1_elapsed = now.elapsed();
-    now = Instant::now();
-    let part2 = part2(input);
-    let part2_elapsed = now.elapsed();
-
-    println!("--- Day 13 ---");
-    println!("Part 1: {:?}", part1);
-    println!("Part 2: {:?}", part2);
-    println!("Part 1 took: {:.2?}", part1_elapsed);
-    println!("Part 2 took: {:.2?}", part2_elapsed);
-
-    assert_eq!(part1, (74, 87));
-    assert_eq!(part2, (29, 74));
+    run::<Day13>();
 }
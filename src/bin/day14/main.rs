@@ -1,19 +1,30 @@
 use itertools::Itertools;
 use num::Integer;
-use std::fs;
-use std::time::Instant;
+
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
 
 type Int = usize;
-type InputType = Int;
+/// The input's digits as written: part 2 looks for them with any leading zeros.
+type InputType = Vec<u8>;
 
 const N: usize = 500_000;
 
-fn read_input() -> InputType {
-    fs::read_to_string("./src/bin/day14/input.txt")
-        .unwrap()
-        .trim()
-        .parse::<Int>()
-        .unwrap()
+/// A run of `d` digits turns up about once every `10^d` recipes, so part 2 gives up on finding the
+/// input's digits after this many times that. It is odds of about `e^-50` against a false
+/// failure.
+const RECIPES_PER_CHANCE: usize = 50;
+
+/// The most recipes either part makes whatever the input, at one byte each in part 2. Puzzle
+/// inputs have 6 digits and turn up in about 20 million.
+const MAX_RECIPES: usize = 50_000_000;
+
+/// How many recipes part 2 makes before giving up on finding `digits`.
+fn recipe_limit(digits: &[u8]) -> usize {
+    u32::try_from(digits.len())
+        .ok()
+        .and_then(|len| 10_usize.checked_pow(len))
+        .and_then(|chance| chance.checked_mul(RECIPES_PER_CHANCE))
+        .map_or(MAX_RECIPES, |limit| limit.min(MAX_RECIPES))
 }
 
 fn get_digits_from_int(mut int: Int) -> Vec<Int> {
@@ -33,75 +44,104 @@ fn get_digits_from_int(mut int: Int) -> Vec<Int> {
     digits
 }
 
-fn part1(input: InputType) -> String {
-    let mut scores = Vec::with_capacity(N);
-    scores.push(3);
-    scores.push(7);
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
 
-    let mut pointer1 = 0;
-    let mut pointer2 = 1;
+    type Input = InputType;
+    type Output1 = String;
+    type Output2 = Int;
 
-    while scores.len() < input + 10 {
-        let score1 = scores[pointer1];
-        let score2 = scores[pointer2];
-        let sum = score1 + score2;
+    fn parse(input: &str, _parser: &mut Parser) -> Result<InputType> {
+        let line = input.trim();
 
-        let digits = get_digits_from_int(sum);
-        scores.extend(digits);
+        if line.is_empty() {
+            return Err(Error::parse(0, line, "expected digits"));
+        }
 
-        pointer1 = (pointer1 + score1 + 1) % scores.len();
-        pointer2 = (pointer2 + score2 + 1) % scores.len();
+        line.chars()
+            .map(|c| {
+                c.to_digit(10)
+                    .map(|digit| digit as u8)
+                    .ok_or_else(|| Error::parse(0, line, format!("{c:?} is not a digit")))
+            })
+            .collect()
     }
 
-    scores[input..input + 10].iter().join("")
-}
+    fn part1(input: InputType) -> Result<String> {
+        let out_of_range =
+            || Error::OutOfRange(format!("part 1 makes at most {MAX_RECIPES} recipes"));
+
+        let input = input
+            .iter()
+            .try_fold(0, |number: Int, &digit| {
+                number.checked_mul(10)?.checked_add(digit as Int)
+            })
+            .ok_or_else(out_of_range)?;
+        let recipes = input
+            .checked_add(10)
+            .filter(|&recipes| recipes <= MAX_RECIPES)
+            .ok_or_else(out_of_range)?;
+
+        let mut scores = Vec::with_capacity(N);
+        scores.push(3);
+        scores.push(7);
+
+        let mut pointer1 = 0;
+        let mut pointer2 = 1;
+
+        while scores.len() < recipes {
+            let score1 = scores[pointer1];
+            let score2 = scores[pointer2];
+            let sum = score1 + score2;
+
+            let digits = get_digits_from_int(sum);
+            scores.extend(digits);
+
+            pointer1 = (pointer1 + score1 + 1) % scores.len();
+            pointer2 = (pointer2 + score2 + 1) % scores.len();
+        }
+
+        Ok(scores[input..recipes].iter().join(""))
+    }
 
-fn part2(input: InputType) -> Int {
-    let input = get_digits_from_int(input);
+    fn part2(input: InputType) -> Result<Int> {
+        // Scores are single digits, so bytes keep the many recipes this may take small.
+        let limit = recipe_limit(&input);
 
-    let mut scores = Vec::with_capacity(N);
-    scores.push(3);
-    scores.push(7);
+        let mut scores: Vec<u8> = Vec::with_capacity(N);
+        scores.push(3);
+        scores.push(7);
 
-    let mut pointer1 = 0;
-    let mut pointer2 = 1;
+        let mut pointer1 = 0;
+        let mut pointer2 = 1;
 
-    loop {
-        let score1 = scores[pointer1];
-        let score2 = scores[pointer2];
-        let sum = score1 + score2;
+        while scores.len() < limit {
+            let score1 = scores[pointer1] as Int;
+            let score2 = scores[pointer2] as Int;
+            let sum = score1 + score2;
 
-        let digits = get_digits_from_int(sum);
+            let digits = get_digits_from_int(sum);
 
-        for digit in digits {
-            scores.push(digit);
+            for digit in digits {
+                scores.push(digit as u8);
 
-            if scores.ends_with(&input) {
-                return scores.len() - input.len();
+                if scores.ends_with(&input) {
+                    return Ok(scores.len() - input.len());
+                }
             }
+
+            pointer1 = (pointer1 + score1 + 1) % scores.len();
+            pointer2 = (pointer2 + score2 + 1) % scores.len();
         }
 
-        pointer1 = (pointer1 + score1 + 1) % scores.len();
-        pointer2 = (pointer2 + score2 + 1) % scores.len();
+        Err(Error::NoSolution(format!(
+            "the digits do not appear in the first {limit} recipes"
+        )))
     }
 }
 
 pub fn main() {
-    let input = read_input();
-
-    let mut now = Instant::now();
-    let part1 = part1(input);
-    let part1_elapsed = now.elapsed();
-    now = Instant::now();
-    let part2 = part2(input);
-    let part2_elapsed = now.elapsed();
-
-    println!("--- Day 14 ---");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
-    println!("Part 1 took: {:.2?}", part1_elapsed);
-    println!("Part 2 took: {:.2?}", part2_elapsed);
-
-    // assert_eq!(part1, 0);
-    // assert_eq!(part2, 0);
+    run::<Day14>();
 }
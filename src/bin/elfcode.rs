@@ -0,0 +1,86 @@
+use std::fmt::Display;
+use std::io::{self, Read};
+use std::process::exit;
+use std::{env, fs};
+
+use advent_of_code_2018::elfcode::samples::{self, Manual};
+use advent_of_code_2018::elfcode::{Opcode, Program};
+use advent_of_code_2018::{ParseMode, Parser};
+
+const USAGE: &str = "Usage: elfcode disasm <program | ->
+       elfcode decompile <program | ->
+       elfcode asm <source | -> [--numbering <samples>]";
+
+fn fail(err: impl Display) -> ! {
+    eprintln!("{err}");
+    exit(1)
+}
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    exit(2)
+}
+
+/// The text at `path`, or on stdin for `-`.
+fn read(path: &str) -> String {
+    if path == "-" {
+        let mut source = String::new();
+        io::stdin()
+            .read_to_string(&mut source)
+            .unwrap_or_else(|err| fail(format!("Cannot read stdin: {err}")));
+        source
+    } else {
+        fs::read_to_string(path).unwrap_or_else(|err| fail(format!("Cannot read {path}: {err}")))
+    }
+}
+
+/// The program at `path`, either plain or as assembly source to be assembled.
+fn load(path: &str, assemble: bool) -> Program {
+    let source = read(path);
+
+    if assemble {
+        return Program::assemble(&source).unwrap_or_else(|err| fail(err));
+    }
+
+    let mut parser = Parser::new(ParseMode::Strict);
+    let program = Program::parse(&source, &mut parser).unwrap_or_else(|err| fail(err));
+    parser.finish().unwrap_or_else(|err| fail(err));
+
+    program
+}
+
+/// The opcode numbering the samples at `path` (e.g. a day 16 input) narrow down to.
+fn numbering(path: &str) -> [Opcode; 16] {
+    let mut parser = Parser::new(ParseMode::Strict);
+    let manual = Manual::parse(&read(path), &mut parser).unwrap_or_else(|err| fail(err));
+    parser.finish().unwrap_or_else(|err| fail(err));
+
+    samples::deduce_opcode_name(&manual.samples).unwrap_or_else(|err| fail(err))
+}
+
+/// The program in the numbered form of day 16's samples, one `number a b c` per line.
+fn print_numbered(program: &Program, numbering: &[Opcode]) {
+    if let Some(register) = program.ip_register {
+        println!("#ip {register}");
+    }
+
+    // Every operation has a number in a deduced numbering.
+    for [number, a, b, c] in program.encode(numbering).unwrap() {
+        println!("{number} {a} {b} {c}");
+    }
+}
+
+fn main() {
+    let args: Vec<_> = env::args().skip(1).collect();
+    let args: Vec<_> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        ["disasm", path] => print!("{}", load(path, false).disassemble()),
+        ["decompile", path] => print!("{}", load(path, false).decompile()),
+        ["asm", path] => print!("{}", load(path, true)),
+        ["asm", path, "--numbering", samples] => {
+            print_numbered(&load(path, true), &numbering(samples))
+        }
+        _ => usage(),
+    }
+}
@@ -1,82 +1,568 @@
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::Path;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::exit;
 use std::{env, fs};
 
 use dotenv::dotenv;
-use reqwest::header::USER_AGENT;
-use reqwest::{blocking, header::COOKIE};
 
-fn fetch_input(day: u8) -> String {
-    let url = format!("https://adventofcode.com/2018/day/{day}/input");
+use advent_of_code_2018::answers::{self, AnswerRegistry};
+use advent_of_code_2018::client::{self, Cache, CachePolicy, Client, ClientError};
+use advent_of_code_2018::input::year_from_args;
+use advent_of_code_2018::session::{self, Session, SessionStore};
+use advent_of_code_2018::submit::{History, Outcome};
+use advent_of_code_2018::{date, leaderboard, puzzle};
+use advent_of_code_2018::{day_dir, days, setup, InputSource, ParseMode, DEFAULT_YEAR};
 
-    let session = env::var("SESSION").expect("AoC session ID must be set");
+const PUZZLE_FILE: &str = "puzzle.md";
 
-    let client = blocking::Client::new();
+const USAGE: &str =
+    "Usage: get_input <day> [--year <year>] [--session <name>] [--offline] [--refresh-input]
+                 [--template <name>] [--example-block <n>] [--input-dir <dir>]
+       get_input submit <day> <1|2> [answer] [--year <year>] [--session <name>] [--input-dir <dir>]
+       get_input leaderboard <id> [ranking|deltas|timelines] [--year <year>] [--session <name>]
+                 [--offline]
+       get_input session [list]
+       get_input session add <name> <token> [--input-dir <dir>] [--no-check]
+       get_input session check [name]
+       get_input session use <name>
+       get_input session remove <name>";
 
-    let res = client
-        .get(url)
-        .header(COOKIE, format!("session={}", session))
-        .header(
-            USER_AGENT,
-            "github.com/alexttyip/advent-of-code-2018 by tsztoyip@gmail.com",
-        )
-        .send()
-        .expect("Error fetching input");
+fn fail(err: impl Display) -> ! {
+    eprintln!("{err}");
+    exit(1)
+}
+
+/// Reports what went wrong without stopping `client`'s requests.
+fn print_warnings(client: &Client) {
+    for err in client.take_warnings() {
+        eprintln!("The request was sent, but not logged: {err}");
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    exit(2)
+}
+
+fn take_value(args: &mut Vec<String>, flag: &str) -> Option<String> {
+    let i = args.iter().position(|arg| arg == flag)?;
+    args.remove(i);
+
+    if i == args.len() {
+        usage();
+    }
+
+    Some(args.remove(i))
+}
+
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != flag);
+
+    args.len() != len
+}
+
+/// The session picked with `--session`, or else the default one (see [`SessionStore::select`]),
+/// with its inputs going to its own directory unless `--input-dir` says otherwise.
+fn session_and_source(args: &mut Vec<String>) -> (Session, InputSource) {
+    let name = take_value(args, "--session");
+    let mut source = InputSource::from_args(args).unwrap_or_else(|err| fail(err));
+
+    let store = SessionStore::load(&session::default_path()).unwrap_or_else(|err| fail(err));
+    let session = store
+        .select(name.as_deref())
+        .unwrap_or_else(|err| fail(err));
+
+    if let InputSource::Day {
+        dir: dir @ None, ..
+    } = &mut source
+    {
+        dir.clone_from(&session.input_dir);
+    }
+
+    (session, source)
+}
+
+/// Solves `day` from its puzzle input, for when no answer is given on the command line.
+fn solve(year: u16, day: u8, part: u8, source: &InputSource) -> String {
+    let Some(solution) = days::find(year, day) else {
+        fail(format!(
+            "Day {day:02} of {year} is not implemented, pass the answer explicitly"
+        ));
+    };
+
+    let raw = source.load(year, day).unwrap_or_else(|err| fail(err));
+    let answers = (solution.solve)(&raw, ParseMode::Strict).unwrap_or_else(|err| {
+        fail(format!(
+            "Day {day:02}: {}: {err}",
+            source.describe(year, day)
+        ))
+    });
+
+    let answer = if part == 1 {
+        answers.part1
+    } else {
+        answers.part2
+    }
+    .unwrap_or_else(|err| fail(format!("Day {day:02} part {part}: {err}")));
+
+    if answer.contains('\n') {
+        fail(format!(
+            "Day {day:02} part {part} has to be read by eye, pass the answer explicitly"
+        ));
+    }
+
+    answer
+}
+
+fn submit(mut args: Vec<String>) {
+    let year = year_from_args(&mut args).unwrap_or_else(|err| fail(err));
+    let (session, source) = session_and_source(&mut args);
+
+    if !source.is_puzzle_input() {
+        usage();
+    }
 
-    if res.status().is_client_error() {
-        panic!("Error fetching input - client error");
-    } else if res.status().is_server_error() {
-        panic!("Error fetching input - server error");
+    let (day, part, answer) = match args.as_slice() {
+        [day, part] => (day, part, None),
+        [day, part, answer] => (day, part, Some(answer.clone())),
+        _ => usage(),
+    };
+
+    let (Ok(day), Ok(part @ (1 | 2))) = (day.parse::<u8>(), part.parse::<u8>()) else {
+        usage();
+    };
+
+    let answer = answer.unwrap_or_else(|| solve(year, day, part, &source));
+
+    // answers.toml holds the answers to the inputs next to the solutions, which other accounts'
+    // answers have nothing to do with.
+    let registry_path = answers::default_path(year);
+    let mut registry = if session.input_dir.is_none() {
+        Some(AnswerRegistry::load(&registry_path).unwrap_or_else(|err| fail(err)))
+    } else {
+        None
+    };
+
+    match registry
+        .as_ref()
+        .and_then(|registry| registry.get(day, part))
+    {
+        Some(known) if known == answer => {
+            println!("Day {day:02} part {part}: {answer} is already known to be correct");
+            return;
+        }
+        Some(known) => fail(format!(
+            "Day {day:02} part {part}: not submitting {answer}, answers.toml has {known}"
+        )),
+        None => {}
+    }
+
+    let client = Client::new(session.token);
+    let history_path = Cache::default().history_path(year, day, &client.session_hash());
+    let mut history = History::load(&history_path).unwrap_or_else(|err| fail(err));
+
+    if let Some(reason) = history.known_wrong(part, &answer) {
+        fail(format!(
+            "Day {day:02} part {part}: not submitting, {reason}"
+        ));
+    }
+
+    let outcome = client.submit(year, day, part, &answer);
+    print_warnings(&client);
+    let outcome = outcome.unwrap_or_else(|err| fail(err));
+
+    println!("Day {day:02} part {part}: {answer} is {outcome}");
+
+    if outcome.is_wrong() {
+        history.record(part, &answer, &outcome);
+        history.save(&history_path).unwrap_or_else(|err| fail(err));
+    }
+
+    if let (Outcome::Correct, Some(registry)) = (&outcome, registry.as_mut()) {
+        registry.record(day, part, &answer);
+        registry
+            .save(&registry_path)
+            .unwrap_or_else(|err| fail(err));
+        println!("Recorded in answers.toml");
     }
 
-    res.text().expect("Error fetching input")
+    if !matches!(outcome, Outcome::Correct | Outcome::AlreadySolved) {
+        exit(1);
+    }
 }
 
-fn setup_day(day: u8) {
-    let day_dir = format!("./src/bin/day{day:02}");
+/// The template called `name`, or a failure listing the ones there are.
+fn template_path(name: &str) -> PathBuf {
+    let template_dir = setup::default_template_dir();
+    let path = template_dir.join(format!("{name}.rs"));
+
+    if !path.is_file() {
+        let templates = setup::templates(&template_dir).unwrap_or_else(|err| fail(err));
+        fail(format!(
+            "No template called {name}, there are: {}",
+            templates.join(", ")
+        ));
+    }
 
-    if Path::new(day_dir.as_str()).exists() {
-        println!("Day dir already exists");
+    path
+}
+
+/// Wires the new day into the runner and the tests.
+fn register(year: u16, day: u8) {
+    let name = setup::module_name(year, day);
+    let days_path = setup::default_days_path();
+    let tests_path = setup::default_tests_path();
+
+    let test_added = setup::register_test(&tests_path, year, day);
+    let test_is_new = matches!(test_added, Ok(true));
+
+    for (path, added) in [
+        (&days_path, setup::register_day(&days_path, year, day)),
+        (&tests_path, test_added),
+    ] {
+        match added {
+            Ok(true) => println!("Registered {name} in {}", path.display()),
+            Ok(false) => {}
+            Err(err) => eprintln!("Cannot register {name}, add it by hand: {err}"),
+        }
+    }
+
+    if test_is_new {
+        println!("{name}_example is ignored, remove its #[ignore] once ex.toml has the answers");
+    }
+}
+
+fn puzzle_is_complete(day_dir: &Path) -> bool {
+    fs::read_to_string(day_dir.join(PUZZLE_FILE))
+        .is_ok_and(|markdown| puzzle::is_complete(&markdown))
+}
+
+/// Writes one of the statement's code blocks to `ex.txt`: block `choice` (counting from 1) if
+/// given, otherwise the likeliest example.
+fn save_example(html: &str, day_dir: &Path, choice: Option<usize>) {
+    let blocks = puzzle::code_blocks(html);
+
+    let index = match choice {
+        Some(choice) if choice > blocks.len() => {
+            eprintln!(
+                "Cannot use code block {choice}, the statement has {}",
+                blocks.len()
+            );
+            return;
+        }
+        Some(choice) => choice - 1,
+        None => match puzzle::likely_example(&blocks) {
+            Some(index) => index,
+            None => {
+                println!("The statement has no code blocks, write ex.txt by hand");
+                return;
+            }
+        },
+    };
+
+    let answer = puzzle::example_answer(html, index);
+
+    if let Err(err) = setup::write_example(day_dir, &blocks[index].text, answer.as_deref()) {
+        eprintln!("{err}");
         return;
     }
 
-    let input = fetch_input(day);
+    println!("Saved code block {} as ex.txt", index + 1);
 
-    let rust_file = fs::read_to_string("./src/bin/template/main.rs")
-        .unwrap()
-        .replace("00", format!("{day:02}").as_str());
+    if choice.is_none() && blocks.len() > 1 {
+        println!(
+            "The statement has {} code blocks, pick another with --example-block <n>:",
+            blocks.len()
+        );
 
-    fs::create_dir(&day_dir).expect("Error creating day dir");
+        for (i, block) in blocks.iter().enumerate() {
+            let first_line = block.text.lines().next().unwrap_or_default();
+            println!("  {} (part {}): {first_line}", i + 1, block.part);
+        }
+    }
+}
+
+/// Saves the statement next to the day's solution, and the example if there is none yet or
+/// `example_block` picks one. A failure here only warns, since the input is what the day cannot do
+/// without.
+fn save_puzzle(client: &Client, year: u16, day: u8, day_dir: &Path, example_block: Option<usize>) {
+    let path = day_dir.join(PUZZLE_FILE);
+
+    let page = client
+        .fetch_puzzle_page(year, day)
+        .and_then(|html| Ok((client.description(year, day, &html)?, html)));
+    print_warnings(client);
+
+    let (description, html) = match page {
+        Ok(page) => page,
+        Err(err) => {
+            eprintln!("Cannot save {PUZZLE_FILE}: {err}");
+            return;
+        }
+    };
+
+    if let Err(err) = setup::write_input(&path, &description.to_markdown()) {
+        eprintln!("{err}");
+    } else if description.has_part2() {
+        println!("Saved {}", path.display());
+    } else {
+        println!(
+            "Saved {}, run again once part 2 is unlocked to add it",
+            path.display()
+        );
+    }
+
+    if example_block.is_some() || !day_dir.join("ex.txt").exists() {
+        save_example(&html, day_dir, example_block);
+    }
+}
+
+fn fetch(mut args: Vec<String>) {
+    let offline = take_flag(&mut args, "--offline");
+    let refresh = take_flag(&mut args, "--refresh-input");
+    let year = year_from_args(&mut args).unwrap_or_else(|err| fail(err));
+    let template = take_value(&mut args, "--template");
+    let example_block = take_value(&mut args, "--example-block").map(|block| match block.parse() {
+        Ok(block @ 1..) => block,
+        _ => usage(),
+    });
+
+    let (session, source) = session_and_source(&mut args);
+
+    let day = match args.as_slice() {
+        [day] if source.is_puzzle_input() => day.parse::<u8>().ok(),
+        _ => None,
+    };
 
-    OpenOptions::new()
-        .write(true)
-        .create_new(true)
-        .open(day_dir.to_string() + "/main.rs")
-        .expect("Cannot open Rust file")
-        .write_all(rust_file.as_bytes())
-        .expect("Error writing input");
+    let Some(day) = day else {
+        usage();
+    };
 
-    OpenOptions::new()
-        .write(true)
-        .create_new(true)
-        .open(day_dir + "/input.txt")
-        .expect("Cannot open input file")
-        .write_all(input.as_bytes())
-        .expect("Error writing input");
+    let src_dir = setup::default_src_dir();
+    let day_dir = day_dir(&src_dir, year, day);
+    let template_path = template_path(template.as_deref().unwrap_or(setup::DEFAULT_TEMPLATE));
+    let client = Client::new(session.token);
+
+    // `--example` and `--input` are rejected above, so this is always the day's `input.txt`.
+    let input_path = source.path(year, day).unwrap();
+
+    if !refresh && input_path.exists() {
+        if !offline && (!puzzle_is_complete(&day_dir) || example_block.is_some()) {
+            save_puzzle(&client, year, day, &day_dir, example_block);
+        }
+
+        println!("Input already exists, use --refresh-input to fetch it again");
+        return;
+    }
+
+    let policy = match (offline, refresh) {
+        (true, _) => CachePolicy::Offline,
+        (false, true) => CachePolicy::Refresh,
+        (false, false) => CachePolicy::Prefer,
+    };
+
+    let input = client::get_input(&client, &Cache::default(), year, day, policy);
+    print_warnings(&client);
+    let input = input.unwrap_or_else(|err| fail(err));
+
+    // Another account's input can arrive after the day is solved, so the solution may be there.
+    let new_day = !day_dir.exists();
+
+    if new_day {
+        setup::create_day(&src_dir, &template_path, year, day).unwrap_or_else(|err| fail(err));
+        register(year, day);
+    }
+
+    setup::write_input(&input_path, &input).unwrap_or_else(|err| fail(err));
+
+    if !offline && (!puzzle_is_complete(&day_dir) || example_block.is_some()) {
+        save_puzzle(&client, year, day, &day_dir, example_block);
+    }
 
     println!("Done! 🚀");
+
+    if new_day && year != DEFAULT_YEAR {
+        // Cargo only finds binaries directly under `src/bin`, so other years run through `aoc`.
+        println!("Run it with `cargo run --bin aoc -- run {day} --year {year}`");
+    }
+}
+
+fn leaderboard(mut args: Vec<String>) {
+    let offline = take_flag(&mut args, "--offline");
+    let year = year_from_args(&mut args).unwrap_or_else(|err| fail(err));
+    let name = take_value(&mut args, "--session");
+
+    let args: Vec<_> = args.iter().map(String::as_str).collect();
+    let (id, report) = match args.as_slice() {
+        [id] => (id, None),
+        [id, report @ ("ranking" | "deltas" | "timelines")] => (id, Some(*report)),
+        _ => usage(),
+    };
+
+    let Ok(id) = id.parse::<u64>() else {
+        usage();
+    };
+
+    let store = SessionStore::load(&session::default_path()).unwrap_or_else(|err| fail(err));
+    let session = store
+        .select(name.as_deref())
+        .unwrap_or_else(|err| fail(err));
+    let client = Client::new(session.token);
+
+    let leaderboard = client::get_leaderboard(&client, &Cache::default(), year, id, offline);
+    print_warnings(&client);
+    let (leaderboard, fetched_at) = leaderboard.unwrap_or_else(|err| fail(err));
+
+    println!(
+        "Leaderboard {id} for {year}, as of {} (fetched at most every {} minutes)",
+        date::format_time(fetched_at),
+        leaderboard::REFRESH_INTERVAL.as_secs() / 60
+    );
+
+    for (heading, name, text) in [
+        ("Ranking", "ranking", leaderboard.ranking()),
+        ("Part 1 to part 2", "deltas", leaderboard.deltas(year)),
+        ("Stars", "timelines", leaderboard.timelines(year)),
+    ] {
+        if report.is_none_or(|report| report == name) {
+            println!("\n{heading}\n\n{text}");
+        }
+    }
+}
+
+/// Checks the session called `name` against the site and remembers who it logs in as. Returns
+/// `false` if the site does not accept it.
+fn check_session(store: &mut SessionStore, name: &str) -> bool {
+    let Some(stored) = store.sessions.get(name) else {
+        fail(ClientError::UnknownSession(name.to_string()));
+    };
+
+    let expiry = date::format_date(stored.expiry_guess());
+
+    let client = Client::new(&stored.token);
+    let user = client.fetch_user();
+    print_warnings(&client);
+
+    match user {
+        Ok(user) => {
+            println!("{name}: logged in as {user}, probably until around {expiry}");
+            store.record_check(name, &user);
+            true
+        }
+        Err(err) => {
+            println!("{name}: {err}");
+            false
+        }
+    }
+}
+
+fn list_sessions(store: &SessionStore) {
+    if store.sessions.is_empty() {
+        println!("No sessions, add one with `get_input session add <name> <token>`");
+    }
+
+    for (name, stored) in &store.sessions {
+        let marker = if store.default.as_deref() == Some(name) {
+            "*"
+        } else {
+            " "
+        };
+
+        let user = match (&stored.user, stored.checked_at) {
+            (Some(user), Some(checked_at)) => format!(
+                "{user} (checked {})",
+                date::format_date(date::from_unix_seconds(checked_at))
+            ),
+            _ => "never checked".to_string(),
+        };
+
+        let input_dir = stored
+            .input_dir
+            .as_ref()
+            .map_or("next to the solutions".to_string(), |dir| {
+                dir.display().to_string()
+            });
+
+        println!(
+            "{marker} {name}: {user}, probably expires around {}, inputs {input_dir}",
+            date::format_date(stored.expiry_guess())
+        );
+    }
+}
+
+fn session_command(mut args: Vec<String>) {
+    let input_dir = take_value(&mut args, "--input-dir").map(PathBuf::from);
+    let no_check = take_flag(&mut args, "--no-check");
+
+    let path = session::default_path();
+    let mut store = SessionStore::load(&path).unwrap_or_else(|err| fail(err));
+    let args: Vec<_> = args.iter().map(String::as_str).collect();
+
+    let ok = match args.as_slice() {
+        [] | ["list"] => {
+            list_sessions(&store);
+            return;
+        }
+        ["add", name, token] => {
+            // Accept the cookie as copied from the browser, with or without its name.
+            let token = token.trim().trim_start_matches("session=");
+            let mut added = store.clone();
+            added.add(name, token, input_dir);
+
+            if !no_check && !check_session(&mut added, name) {
+                fail(format!("Not storing {name}, the site does not accept it"));
+            }
+
+            store = added;
+            println!("Stored session {name}");
+            true
+        }
+        ["check"] => {
+            let Some(name) = store.default.clone() else {
+                fail(ClientError::MissingSession);
+            };
+
+            check_session(&mut store, &name)
+        }
+        ["check", name] => check_session(&mut store, name),
+        ["use", name] if store.set_default(name) => {
+            println!("Using {name} by default");
+            true
+        }
+        ["remove", name] if store.remove(name) => {
+            println!("Removed session {name}");
+            true
+        }
+        ["use" | "remove", name] => fail(ClientError::UnknownSession(name.to_string())),
+        _ => usage(),
+    };
+
+    store.save(&path).unwrap_or_else(|err| fail(err));
+
+    if !ok {
+        exit(1);
+    }
 }
 
 fn main() {
     dotenv().ok();
 
-    let args: Vec<_> = env::args().collect();
-    let day = args
-        .get(1)
-        .expect("Day number not found in argument")
-        .parse()
-        .expect("Argument is not a number");
+    let mut args: Vec<_> = env::args().skip(1).collect();
 
-    setup_day(day);
+    match args.first().map(String::as_str) {
+        Some("submit") => {
+            args.remove(0);
+            submit(args);
+        }
+        Some("leaderboard") => {
+            args.remove(0);
+            leaderboard(args);
+        }
+        Some("session") => {
+            args.remove(0);
+            session_command(args);
+        }
+        _ => fetch(args),
+    }
 }
@@ -0,0 +1,612 @@
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{env, error};
+
+use reqwest::blocking;
+use reqwest::header::{COOKIE, USER_AGENT};
+use reqwest::StatusCode;
+
+use crate::date;
+use crate::leaderboard::{self, Leaderboard};
+use crate::puzzle::Description;
+use crate::rate_limit::RateLimit;
+use crate::submit::Outcome;
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+pub const SESSION_ENV: &str = "SESSION";
+pub const CACHE_DIR_ENV: &str = "AOC_CACHE_DIR";
+
+const USER_AGENT_VALUE: &str = concat!(
+    "github.com/alexttyip/",
+    env!("CARGO_PKG_NAME"),
+    " by tsztoyip@gmail.com"
+);
+
+/// Puzzles unlock at midnight US Eastern time, which is 05:00 UTC in December.
+const UNLOCK_HOUR_UTC: u64 = 5;
+
+#[derive(Debug)]
+pub enum ClientError {
+    MissingSession,
+    UnknownSession(String),
+    /// The request never got an answer, even after retrying.
+    Http(reqwest::Error),
+    /// The session is missing, malformed or has expired, so the site asks to log in.
+    Unauthorized {
+        url: String,
+    },
+    NotYetAvailable {
+        year: u16,
+        day: u8,
+        unlocks_at: SystemTime,
+    },
+    NotFound {
+        url: String,
+    },
+    /// The site kept failing with a 5xx, even after retrying.
+    Server {
+        url: String,
+        status: u16,
+    },
+    /// Sending would make a burst of requests, see [`RateLimit`].
+    RateLimited {
+        retry_at: SystemTime,
+    },
+    /// Any other status the site should not answer with.
+    Status {
+        url: String,
+        status: u16,
+    },
+    NotCached {
+        year: u16,
+        day: u8,
+    },
+    LeaderboardNotCached {
+        year: u16,
+        id: u64,
+    },
+    /// The site answered with a page we do not know how to read.
+    UnexpectedResponse {
+        url: String,
+    },
+    Cache {
+        path: PathBuf,
+        source: io::Error,
+    },
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::MissingSession => write!(
+                f,
+                "No session: set {SESSION_ENV} (e.g. in .env) or add one with `get_input session add`"
+            ),
+            ClientError::UnknownSession(name) => {
+                write!(f, "No session called {name}, see `get_input session list`")
+            }
+            ClientError::Http(err) => write!(f, "Request failed: {err}"),
+            ClientError::Unauthorized { url } => write!(
+                f,
+                "{url} asked to log in, the session is invalid or has expired"
+            ),
+            ClientError::NotYetAvailable {
+                year,
+                day,
+                unlocks_at,
+            } => {
+                write!(
+                    f,
+                    "{year} day {day:02} is not available yet, it unlocks at \
+                     {year}-12-{day:02} {UNLOCK_HOUR_UTC:02}:00 UTC"
+                )?;
+
+                match unlocks_at.duration_since(SystemTime::now()) {
+                    Ok(left) => write!(f, " (in {})", format_wait(left)),
+                    Err(_) => Ok(()),
+                }
+            }
+            ClientError::NotFound { url } => write!(f, "{url} does not exist"),
+            ClientError::Server { url, status } => {
+                write!(f, "{url} kept failing with HTTP {status}")
+            }
+            ClientError::RateLimited { retry_at } => write!(
+                f,
+                "Refusing to send a burst of requests to the site, try again after {}",
+                date::format_time(*retry_at)
+            ),
+            ClientError::Status { url, status } => write!(f, "{url} returned HTTP {status}"),
+            ClientError::NotCached { year, day } => {
+                write!(
+                    f,
+                    "Input for {year} day {day:02} is not cached, cannot fetch it offline"
+                )
+            }
+            ClientError::LeaderboardNotCached { year, id } => {
+                write!(
+                    f,
+                    "Leaderboard {id} for {year} is not cached, cannot fetch it offline"
+                )
+            }
+            ClientError::UnexpectedResponse { url } => {
+                write!(f, "Unexpected response from {url}")
+            }
+            ClientError::Cache { path, source } => {
+                write!(f, "Cannot access cache at {}: {source}", path.display())
+            }
+        }
+    }
+}
+
+impl error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ClientError::Http(err) => Some(err),
+            ClientError::Cache { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+fn format_wait(wait: Duration) -> String {
+    let minutes = wait.as_secs().div_ceil(60);
+
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, minutes) => format!("{hours}h {minutes}m"),
+    }
+}
+
+/// When the puzzle for `day` of `year` becomes available.
+pub fn unlock_time(year: u16, day: u8) -> SystemTime {
+    let days = date::days_from_civil(year.into(), 12, day.into()).max(0) as u64;
+
+    UNIX_EPOCH + Duration::from_secs(days * 86_400 + UNLOCK_HOUR_UTC * 3600)
+}
+
+/// Whether `body` is an HTML page rather than plain text. The site serves its login page with a
+/// 200 to a request for an input whose session is not accepted.
+fn is_html(body: &str) -> bool {
+    let start: String = body.trim_start().chars().take(14).collect();
+    let start = start.to_ascii_lowercase();
+
+    start.starts_with("<!doctype html") || start.starts_with("<html")
+}
+
+/// How often a request that failed for reasons outside our control is tried again.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Retry {
+    /// Attempts in total, including the first one.
+    pub attempts: u32,
+    /// The wait after the first failure, doubled after each further one.
+    pub backoff: Duration,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Retry {
+            attempts: 3,
+            backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+/// FNV-1a, which is stable across Rust versions, unlike `DefaultHasher`. The hash only has to
+/// keep different sessions apart in the cache, it does not have to be hard to reverse.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Talks to the Advent of Code site (or whatever `$AOC_BASE_URL` points at) as one user.
+pub struct Client {
+    base_url: String,
+    session: String,
+    http: blocking::Client,
+    retry: Retry,
+    rate_limit: RateLimit,
+    /// Failures that did not stop a request, see [`Client::take_warnings`].
+    warnings: Mutex<Vec<ClientError>>,
+}
+
+impl Client {
+    pub fn new(session: impl Into<String>) -> Client {
+        Client {
+            base_url: env::var(BASE_URL_ENV).unwrap_or_else(|_| BASE_URL.to_string()),
+            session: session.into(),
+            http: blocking::Client::new(),
+            retry: Retry::default(),
+            rate_limit: RateLimit::default(),
+            warnings: Mutex::default(),
+        }
+    }
+
+    /// A client for the session in `$SESSION`.
+    pub fn from_env() -> Result<Client, ClientError> {
+        env::var(SESSION_ENV)
+            .map(Client::new)
+            .map_err(|_| ClientError::MissingSession)
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Client {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_retry(mut self, retry: Retry) -> Client {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Client {
+        self.rate_limit = rate_limit;
+        self
+    }
+
+    /// Identifies the session in cache paths without storing the session itself.
+    pub fn session_hash(&self) -> String {
+        format!("{:016x}", fnv1a(self.session.as_bytes()))
+    }
+
+    /// Failures since the last call that did not stop a request, oldest first: a request that was
+    /// sent but could not be written to the request log.
+    pub fn take_warnings(&self) -> Vec<ClientError> {
+        std::mem::take(&mut self.warnings.lock().unwrap())
+    }
+
+    /// Sends `request` once as the session's user, within the rate limit, and returns the body of
+    /// a successful response.
+    fn send(&self, request: blocking::RequestBuilder, url: &str) -> Result<String, ClientError> {
+        let request = request
+            .header(COOKIE, format!("session={}", self.session))
+            .header(USER_AGENT, USER_AGENT_VALUE)
+            .build()
+            .map_err(ClientError::Http)?;
+        let method = request.method().to_string();
+
+        self.rate_limit.wait()?;
+
+        let sent_at = SystemTime::now();
+        let res = self.http.execute(request);
+
+        let outcome = match &res {
+            Ok(res) => res.status().as_u16().to_string(),
+            Err(_) => "failed".to_string(),
+        };
+
+        // The request has reached the site by now, so its response must not be lost to a log that
+        // cannot be written, least of all for an answer that was submitted.
+        if let Err(err) = self.rate_limit.record(sent_at, &method, url, &outcome) {
+            self.warnings.lock().unwrap().push(err);
+        }
+
+        let res = res.map_err(ClientError::Http)?;
+
+        let url = url.to_string();
+        let status = res.status();
+
+        match status {
+            _ if status.is_success() => res.text().map_err(ClientError::Http),
+            StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                Err(ClientError::Unauthorized { url })
+            }
+            StatusCode::NOT_FOUND => Err(ClientError::NotFound { url }),
+            _ if status.is_server_error() => Err(ClientError::Server {
+                url,
+                status: status.as_u16(),
+            }),
+            _ => Err(ClientError::Status {
+                url,
+                status: status.as_u16(),
+            }),
+        }
+    }
+
+    /// Gets `url`, trying again with backoff while the site fails or cannot be reached. Only for
+    /// requests that are safe to repeat.
+    fn get(&self, url: &str) -> Result<String, ClientError> {
+        let mut backoff = self.retry.backoff;
+
+        for _ in 1..self.retry.attempts {
+            match self.send(self.http.get(url), url) {
+                Err(ClientError::Http(_) | ClientError::Server { .. }) => {
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                }
+                result => return result,
+            }
+        }
+
+        self.send(self.http.get(url), url)
+    }
+
+    /// A 404 for a puzzle that has not unlocked yet says so.
+    fn locked(year: u16, day: u8, err: ClientError) -> ClientError {
+        match err {
+            ClientError::NotFound { .. } if SystemTime::now() < unlock_time(year, day) => {
+                ClientError::NotYetAvailable {
+                    year,
+                    day,
+                    unlocks_at: unlock_time(year, day),
+                }
+            }
+            err => err,
+        }
+    }
+
+    /// The name the site shows for the session's user, from a page that needs logging in. Fails
+    /// with [`ClientError::Unauthorized`] if the session is not logged in.
+    pub fn fetch_user(&self) -> Result<String, ClientError> {
+        const USER_START: &str = "<div class=\"user\">";
+
+        let url = format!("{}/settings", self.base_url);
+        let html = self.get(&url)?;
+
+        html.find(USER_START)
+            .map(|start| &html[start + USER_START.len()..])
+            .and_then(|rest| rest.split('<').next())
+            .map(str::trim)
+            .filter(|user| !user.is_empty())
+            .map(String::from)
+            .ok_or(ClientError::Unauthorized { url })
+    }
+
+    pub fn fetch_input(&self, year: u16, day: u8) -> Result<String, ClientError> {
+        let url = format!("{}/{year}/day/{day}/input", self.base_url);
+
+        match self.get(&url) {
+            Ok(body) if is_html(&body) => Err(ClientError::Unauthorized { url }),
+            result => result.map_err(|err| Self::locked(year, day, err)),
+        }
+    }
+
+    fn puzzle_url(&self, year: u16, day: u8) -> String {
+        format!("{}/{year}/day/{day}", self.base_url)
+    }
+
+    /// The HTML of the puzzle's page, with part 2 once the session has solved part 1.
+    pub fn fetch_puzzle_page(&self, year: u16, day: u8) -> Result<String, ClientError> {
+        self.get(&self.puzzle_url(year, day))
+            .map_err(|err| Self::locked(year, day, err))
+    }
+
+    /// Reads the statement out of a page from [`Client::fetch_puzzle_page`].
+    pub fn description(&self, year: u16, day: u8, html: &str) -> Result<Description, ClientError> {
+        Description::from_html(html, &self.base_url).ok_or(ClientError::UnexpectedResponse {
+            url: self.puzzle_url(year, day),
+        })
+    }
+
+    /// The puzzle's statement, with part 2 once the session has solved part 1.
+    pub fn fetch_description(&self, year: u16, day: u8) -> Result<Description, ClientError> {
+        let html = self.fetch_puzzle_page(year, day)?;
+
+        self.description(year, day, &html)
+    }
+
+    fn leaderboard_url(&self, year: u16, id: u64) -> String {
+        format!(
+            "{}/{year}/leaderboard/private/view/{id}.json",
+            self.base_url
+        )
+    }
+
+    /// The JSON of a private leaderboard the session's user is a member of. Mind
+    /// [`leaderboard::REFRESH_INTERVAL`]; [`get_leaderboard`] does.
+    pub fn fetch_leaderboard(&self, year: u16, id: u64) -> Result<String, ClientError> {
+        let url = self.leaderboard_url(year, id);
+
+        match self.get(&url) {
+            // Not a member, or not logged in: the site redirects to a page instead.
+            Ok(body) if is_html(&body) => Err(ClientError::Unauthorized { url }),
+            result => result,
+        }
+    }
+
+    /// Posts `answer` for one part of a puzzle. Never retried, since a repeated answer could count
+    /// against the rate limit.
+    pub fn submit(
+        &self,
+        year: u16,
+        day: u8,
+        part: u8,
+        answer: &str,
+    ) -> Result<Outcome, ClientError> {
+        let url = format!("{}/{year}/day/{day}/answer", self.base_url);
+
+        let level = part.to_string();
+        let request = self
+            .http
+            .post(&url)
+            .form(&[("level", level.as_str()), ("answer", answer)]);
+        let html = self.send(request, &url)?;
+
+        Outcome::from_html(&html).ok_or(ClientError::UnexpectedResponse { url })
+    }
+}
+
+/// Puzzle inputs already downloaded, stored as `<dir>/<year>/dayNN/<session hash>.txt` so that
+/// inputs from different accounts never mix.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Default for Cache {
+    /// `$AOC_CACHE_DIR`, or `.aoc-cache` in the repository.
+    fn default() -> Self {
+        Cache::new(
+            env::var_os(CACHE_DIR_ENV)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join(".aoc-cache")),
+        )
+    }
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Cache {
+        Cache { dir: dir.into() }
+    }
+
+    pub fn path(&self, year: u16, day: u8, session_hash: &str) -> PathBuf {
+        self.dir
+            .join(year.to_string())
+            .join(format!("day{day:02}"))
+            .join(format!("{session_hash}.txt"))
+    }
+
+    /// Where the wrong answers submitted for the day are kept.
+    pub fn history_path(&self, year: u16, day: u8, session_hash: &str) -> PathBuf {
+        self.path(year, day, session_hash)
+            .with_extension("submissions.toml")
+    }
+
+    /// Where a private leaderboard is kept, per session since only members can see it.
+    pub fn leaderboard_path(&self, year: u16, id: u64, session_hash: &str) -> PathBuf {
+        self.dir
+            .join(year.to_string())
+            .join(format!("leaderboard-{id}"))
+            .join(format!("{session_hash}.json"))
+    }
+
+    pub fn get(
+        &self,
+        year: u16,
+        day: u8,
+        session_hash: &str,
+    ) -> Result<Option<String>, ClientError> {
+        let path = self.path(year, day, session_hash);
+
+        match fs::read_to_string(&path) {
+            Ok(input) => Ok(Some(input)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(ClientError::Cache { path, source }),
+        }
+    }
+
+    pub fn put(
+        &self,
+        year: u16,
+        day: u8,
+        session_hash: &str,
+        input: &str,
+    ) -> Result<(), ClientError> {
+        write(self.path(year, day, session_hash), input)
+    }
+
+    /// A cached leaderboard and when it was fetched.
+    pub fn get_leaderboard(
+        &self,
+        year: u16,
+        id: u64,
+        session_hash: &str,
+    ) -> Result<Option<(String, SystemTime)>, ClientError> {
+        let path = self.leaderboard_path(year, id, session_hash);
+
+        let read =
+            fs::read_to_string(&path).and_then(|json| Ok((json, fs::metadata(&path)?.modified()?)));
+
+        match read {
+            Ok(cached) => Ok(Some(cached)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(ClientError::Cache { path, source }),
+        }
+    }
+
+    pub fn put_leaderboard(
+        &self,
+        year: u16,
+        id: u64,
+        session_hash: &str,
+        json: &str,
+    ) -> Result<(), ClientError> {
+        write(self.leaderboard_path(year, id, session_hash), json)
+    }
+}
+
+fn write(path: PathBuf, contents: &str) -> Result<(), ClientError> {
+    path.parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, contents))
+        .map_err(|source| ClientError::Cache { path, source })
+}
+
+/// When to go to the network for an input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Use the cached input if there is one, otherwise fetch and cache it.
+    Prefer,
+    /// Always fetch, replacing the cached input.
+    Refresh,
+    /// Never fetch.
+    Offline,
+}
+
+/// The input for `day`, from the cache or the site depending on `policy`.
+pub fn get_input(
+    client: &Client,
+    cache: &Cache,
+    year: u16,
+    day: u8,
+    policy: CachePolicy,
+) -> Result<String, ClientError> {
+    let session_hash = client.session_hash();
+
+    if policy != CachePolicy::Refresh {
+        if let Some(input) = cache.get(year, day, &session_hash)? {
+            return Ok(input);
+        }
+    }
+
+    if policy == CachePolicy::Offline {
+        return Err(ClientError::NotCached { year, day });
+    }
+
+    let input = client.fetch_input(year, day)?;
+    cache.put(year, day, &session_hash, &input)?;
+
+    Ok(input)
+}
+
+/// A private leaderboard and when it was fetched. The cached copy is used while it is younger than
+/// [`leaderboard::REFRESH_INTERVAL`], so the site is never asked more often than it allows, and
+/// always when `offline`.
+pub fn get_leaderboard(
+    client: &Client,
+    cache: &Cache,
+    year: u16,
+    id: u64,
+    offline: bool,
+) -> Result<(Leaderboard, SystemTime), ClientError> {
+    let session_hash = client.session_hash();
+
+    if let Some((json, fetched_at)) = cache.get_leaderboard(year, id, &session_hash)? {
+        let fresh = fetched_at
+            .elapsed()
+            .is_ok_and(|age| age < leaderboard::REFRESH_INTERVAL);
+
+        if let (true, Ok(leaderboard)) = (fresh || offline, Leaderboard::from_json(&json)) {
+            return Ok((leaderboard, fetched_at));
+        }
+    }
+
+    if offline {
+        return Err(ClientError::LeaderboardNotCached { year, id });
+    }
+
+    let json = client.fetch_leaderboard(year, id)?;
+    let leaderboard =
+        Leaderboard::from_json(&json).map_err(|_| ClientError::UnexpectedResponse {
+            url: client.leaderboard_url(year, id),
+        })?;
+    cache.put_leaderboard(year, id, &session_hash, &json)?;
+
+    Ok((leaderboard, SystemTime::now()))
+}
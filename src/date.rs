@@ -0,0 +1,84 @@
+//! Just enough calendar arithmetic for puzzle unlock times and the dates the site reports, which
+//! are all in UTC.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Days from 1970-01-01 to the given date, after Howard Hinnant's `days_from_civil`.
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The date `days` after 1970-01-01, as `(year, month, day)`; the inverse of [`days_from_civil`].
+pub fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+
+    (year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// Seconds since the Unix epoch; times before it count as the epoch.
+pub fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+pub fn from_unix_seconds(seconds: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+/// `YYYY-MM-DD`.
+pub fn format_date(time: SystemTime) -> String {
+    let (year, month, day) = civil_from_days((unix_seconds(time) / 86_400) as i64);
+
+    format!("{year}-{month:02}-{day:02}")
+}
+
+/// `YYYY-MM-DD HH:MM:SS UTC`.
+pub fn format_time(time: SystemTime) -> String {
+    let seconds = unix_seconds(time) % 86_400;
+
+    format!(
+        "{} {:02}:{:02}:{:02} UTC",
+        format_date(time),
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_dates() {
+        for days in [-1, 0, 59, 365, 10_957, 17_866, 19_000, 50_000] {
+            let (year, month, day) = civil_from_days(days);
+
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+
+        assert_eq!(civil_from_days(17_866), (2018, 12, 1));
+        assert_eq!(
+            format_time(from_unix_seconds(1_543_640_400 + 61)),
+            "2018-12-01 05:01:01 UTC"
+        );
+    }
+}
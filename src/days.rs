@@ -0,0 +1,78 @@
+//! Every day compiled into the library, for the runner, the benchmarks and the tests. Days of
+//! other events come from `bin/<year>/dayNN/main.rs` and are named `y<year>_dayNN`.
+
+use crate::bench::{bench, BenchConfig, DayBench};
+use crate::example::{check, ExampleReport, Expected};
+use crate::{solve_erased, DayAnswers, ParseMode, Result, Solution};
+
+#[path = "bin/day01/main.rs"]
+pub mod day01;
+#[path = "bin/day02/main.rs"]
+pub mod day02;
+#[path = "bin/day03/main.rs"]
+pub mod day03;
+#[path = "bin/day04/main.rs"]
+pub mod day04;
+#[path = "bin/day05/main.rs"]
+pub mod day05;
+#[path = "bin/day06/main.rs"]
+pub mod day06;
+#[path = "bin/day07/main.rs"]
+pub mod day07;
+#[path = "bin/day08/main.rs"]
+pub mod day08;
+#[path = "bin/day09/main.rs"]
+pub mod day09;
+#[path = "bin/day10/main.rs"]
+pub mod day10;
+#[path = "bin/day11/main.rs"]
+pub mod day11;
+#[path = "bin/day12/main.rs"]
+pub mod day12;
+#[path = "bin/day13/main.rs"]
+pub mod day13;
+#[path = "bin/day14/main.rs"]
+pub mod day14;
+#[path = "bin/day16/main.rs"]
+pub mod day16;
+
+/// A registered day, with its output types erased so that days can be run side by side.
+pub struct Day {
+    pub year: u16,
+    pub day: u8,
+    pub solve: fn(&str, ParseMode) -> Result<DayAnswers>,
+    pub check_example: fn(&str, &Expected) -> Result<ExampleReport>,
+    pub bench: fn(&str, ParseMode, &BenchConfig) -> Result<DayBench>,
+}
+
+const fn day<S: Solution>() -> Day {
+    Day {
+        year: S::YEAR,
+        day: S::DAY,
+        solve: solve_erased::<S>,
+        check_example: check::<S>,
+        bench: bench::<S>,
+    }
+}
+
+pub const DAYS: &[Day] = &[
+    day::<day01::Day01>(),
+    day::<day02::Day02>(),
+    day::<day03::Day03>(),
+    day::<day04::Day04>(),
+    day::<day05::Day05>(),
+    day::<day06::Day06>(),
+    day::<day07::Day07>(),
+    day::<day08::Day08>(),
+    day::<day09::Day09>(),
+    day::<day10::Day10>(),
+    day::<day11::Day11>(),
+    day::<day12::Day12>(),
+    day::<day13::Day13>(),
+    day::<day14::Day14>(),
+    day::<day16::Day16>(),
+];
+
+pub fn find(year: u16, day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.year == year && d.day == day)
+}
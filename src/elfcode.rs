@@ -0,0 +1,566 @@
+//! The wrist device's instruction set, which several puzzles share: sixteen operations on a
+//! handful of registers, each taking two inputs `a` and `b` and writing register `c`.
+//!
+//! Programs are written one instruction per line, optionally binding the instruction pointer to a
+//! register first:
+//!
+//! ```text
+//! #ip 0
+//! seti 5 0 1
+//! addi 0 1 0
+//! ```
+
+use std::error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::num::ParseIntError;
+use std::ops::{BitAnd, BitOr};
+use std::str::FromStr;
+
+use crate::{Error, Parser, Result};
+
+pub mod assemble;
+pub mod decompile;
+pub mod disassemble;
+pub mod samples;
+
+pub type Int = usize;
+
+/// What registers hold. Arithmetic wraps, as the device's does, instead of overflowing.
+pub trait Word:
+    Copy
+    + Default
+    + Eq
+    + Ord
+    + Debug
+    + Display
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + FromStr<Err = ParseIntError>
+{
+    fn wrapping_add(self, other: Self) -> Self;
+
+    fn wrapping_mul(self, other: Self) -> Self;
+
+    fn from_bool(value: bool) -> Self;
+
+    /// The word as a register number or instruction pointer, if it can be one.
+    fn to_index(self) -> Option<usize>;
+
+    /// An instruction pointer as a word, for the register it is bound to.
+    fn from_index(index: usize) -> Self;
+}
+
+macro_rules! impl_word {
+    ($($word:ty),*) => {
+        $(
+            impl Word for $word {
+                fn wrapping_add(self, other: Self) -> Self {
+                    <$word>::wrapping_add(self, other)
+                }
+
+                fn wrapping_mul(self, other: Self) -> Self {
+                    <$word>::wrapping_mul(self, other)
+                }
+
+                fn from_bool(value: bool) -> Self {
+                    <$word>::from(value)
+                }
+
+                fn to_index(self) -> Option<usize> {
+                    usize::try_from(self).ok()
+                }
+
+                fn from_index(index: usize) -> Self {
+                    index as $word
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(u32, u64, usize, i64);
+
+/// An instruction named a register the device does not have.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RegisterError<W = Int> {
+    pub register: W,
+    pub registers: usize,
+}
+
+impl<W: Word> Display for RegisterError<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "register {} does not exist, there are {}",
+            self.register, self.registers
+        )
+    }
+}
+
+impl<W: Word> error::Error for RegisterError<W> {}
+
+impl<W: Word> From<RegisterError<W>> for Error {
+    fn from(err: RegisterError<W>) -> Error {
+        Error::Invalid(err.to_string())
+    }
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Opcode {
+    Addr,
+    Addi,
+    Mulr,
+    Muli,
+    Banr,
+    Bani,
+    Borr,
+    Bori,
+    Setr,
+    Seti,
+    Gtir,
+    Gtri,
+    Gtrr,
+    Eqir,
+    Eqri,
+    Eqrr,
+}
+
+pub const OPCODES: [Opcode; 16] = [
+    Opcode::Addr,
+    Opcode::Addi,
+    Opcode::Mulr,
+    Opcode::Muli,
+    Opcode::Banr,
+    Opcode::Bani,
+    Opcode::Borr,
+    Opcode::Bori,
+    Opcode::Setr,
+    Opcode::Seti,
+    Opcode::Gtir,
+    Opcode::Gtri,
+    Opcode::Gtrr,
+    Opcode::Eqir,
+    Opcode::Eqri,
+    Opcode::Eqrr,
+];
+
+/// How an operation reads one of its inputs.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Mode {
+    Register,
+    Immediate,
+    Ignored,
+}
+
+fn register<W: Word>(registers: &[W], index: W) -> std::result::Result<usize, RegisterError<W>> {
+    index
+        .to_index()
+        .filter(|&i| i < registers.len())
+        .ok_or(RegisterError {
+            register: index,
+            registers: registers.len(),
+        })
+}
+
+impl Opcode {
+    /// The name programs use for the operation, e.g. `addi`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Opcode::Addr => "addr",
+            Opcode::Addi => "addi",
+            Opcode::Mulr => "mulr",
+            Opcode::Muli => "muli",
+            Opcode::Banr => "banr",
+            Opcode::Bani => "bani",
+            Opcode::Borr => "borr",
+            Opcode::Bori => "bori",
+            Opcode::Setr => "setr",
+            Opcode::Seti => "seti",
+            Opcode::Gtir => "gtir",
+            Opcode::Gtri => "gtri",
+            Opcode::Gtrr => "gtrr",
+            Opcode::Eqir => "eqir",
+            Opcode::Eqri => "eqri",
+            Opcode::Eqrr => "eqrr",
+        }
+    }
+
+    /// How the operation reads `a` and `b`.
+    pub fn modes(self) -> [Mode; 2] {
+        match self {
+            Opcode::Addr
+            | Opcode::Mulr
+            | Opcode::Banr
+            | Opcode::Borr
+            | Opcode::Gtrr
+            | Opcode::Eqrr => [Mode::Register, Mode::Register],
+            Opcode::Addi
+            | Opcode::Muli
+            | Opcode::Bani
+            | Opcode::Bori
+            | Opcode::Gtri
+            | Opcode::Eqri => [Mode::Register, Mode::Immediate],
+            Opcode::Gtir | Opcode::Eqir => [Mode::Immediate, Mode::Register],
+            Opcode::Setr => [Mode::Register, Mode::Ignored],
+            Opcode::Seti => [Mode::Immediate, Mode::Ignored],
+        }
+    }
+
+    /// Applies the operation to `registers`. Whether `a` and `b` name registers or are values
+    /// depends on the operation (`r` for register, `i` for immediate); `c` is always a register.
+    /// Registers are left as they were if any of them does not exist.
+    pub fn execute<W: Word>(
+        self,
+        registers: &mut [W],
+        [a, b, c]: [W; 3],
+    ) -> std::result::Result<(), RegisterError<W>> {
+        let r = |i: W| register(registers, i).map(|i| registers[i]);
+
+        let value = match self {
+            Opcode::Addr => r(a)?.wrapping_add(r(b)?),
+            Opcode::Addi => r(a)?.wrapping_add(b),
+            Opcode::Mulr => r(a)?.wrapping_mul(r(b)?),
+            Opcode::Muli => r(a)?.wrapping_mul(b),
+            Opcode::Banr => r(a)? & r(b)?,
+            Opcode::Bani => r(a)? & b,
+            Opcode::Borr => r(a)? | r(b)?,
+            Opcode::Bori => r(a)? | b,
+            Opcode::Setr => r(a)?,
+            Opcode::Seti => a,
+            Opcode::Gtir => W::from_bool(a > r(b)?),
+            Opcode::Gtri => W::from_bool(r(a)? > b),
+            Opcode::Gtrr => W::from_bool(r(a)? > r(b)?),
+            Opcode::Eqir => W::from_bool(a == r(b)?),
+            Opcode::Eqri => W::from_bool(r(a)? == b),
+            Opcode::Eqrr => W::from_bool(r(a)? == r(b)?),
+        };
+
+        registers[register(registers, c)?] = value;
+
+        Ok(())
+    }
+}
+
+impl Display for Opcode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Opcode {
+    type Err = String;
+
+    fn from_str(name: &str) -> std::result::Result<Opcode, String> {
+        OPCODES
+            .into_iter()
+            .find(|opcode| opcode.name() == name)
+            .ok_or_else(|| format!("unknown opcode {name}"))
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Instruction<W = Int> {
+    pub opcode: Opcode,
+    pub a: W,
+    pub b: W,
+    pub c: W,
+}
+
+impl<W: Word> Instruction<W> {
+    pub fn new(opcode: Opcode, [a, b, c]: [W; 3]) -> Instruction<W> {
+        Instruction { opcode, a, b, c }
+    }
+
+    pub fn execute(&self, registers: &mut [W]) -> std::result::Result<(), RegisterError<W>> {
+        self.opcode.execute(registers, [self.a, self.b, self.c])
+    }
+
+    /// The registers the instruction reads.
+    pub fn reads(&self) -> Vec<W> {
+        self.opcode
+            .modes()
+            .into_iter()
+            .zip([self.a, self.b])
+            .filter(|(mode, _)| *mode == Mode::Register)
+            .map(|(_, register)| register)
+            .collect()
+    }
+
+    /// Parses the `i`th line as `opcode a b c`.
+    pub fn parse(i: usize, line: &str) -> Result<Instruction<W>> {
+        let format = || Error::parse(i, line, "expected \"opcode a b c\"");
+
+        let (opcode, operands) = line.trim().split_once(' ').ok_or_else(format)?;
+        let opcode = opcode.parse().map_err(|err| Error::parse(i, line, err))?;
+        let operands = operands
+            .split_whitespace()
+            .map(|operand| operand.parse().map_err(|err| Error::parse(i, line, err)))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Instruction::new(
+            opcode,
+            operands.try_into().map_err(|_| format())?,
+        ))
+    }
+}
+
+impl<W: Word> Display for Instruction<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} {}", self.opcode, self.a, self.b, self.c)
+    }
+}
+
+const IP_DIRECTIVE: &str = "#ip ";
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Program<W = Int> {
+    /// The register the instruction pointer is bound to by `#ip N`, if any.
+    pub ip_register: Option<usize>,
+    pub instructions: Vec<Instruction<W>>,
+}
+
+impl<W> Default for Program<W> {
+    fn default() -> Self {
+        Program {
+            ip_register: None,
+            instructions: vec![],
+        }
+    }
+}
+
+impl<W> From<Vec<Instruction<W>>> for Program<W> {
+    fn from(instructions: Vec<Instruction<W>>) -> Program<W> {
+        Program {
+            ip_register: None,
+            instructions,
+        }
+    }
+}
+
+impl<W: Word> Program<W> {
+    /// Parses a program, skipping blank lines. Lines that do not parse go to `parser`.
+    pub fn parse(input: &str, parser: &mut Parser) -> Result<Program<W>> {
+        let mut program = Program::default();
+
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let Some(register) = line.strip_prefix(IP_DIRECTIVE) else {
+                program
+                    .instructions
+                    .extend(parser.line(Instruction::parse(i, line)));
+                continue;
+            };
+
+            if program.ip_register.is_some() {
+                return Err(Error::parse(
+                    i,
+                    line,
+                    "the instruction pointer is already bound",
+                ));
+            }
+
+            let register = register
+                .trim()
+                .parse()
+                .map_err(|err| Error::parse(i, line, err))?;
+            program.ip_register = Some(register);
+        }
+
+        Ok(program)
+    }
+}
+
+impl<W: Word> Display for Program<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(register) = self.ip_register {
+            writeln!(f, "{IP_DIRECTIVE}{register}")?;
+        }
+
+        for instruction in &self.instructions {
+            writeln!(f, "{instruction}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A device with `N` registers of type `W` running a program, starting with every register at 0.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Machine<const N: usize, W = Int> {
+    pub registers: [W; N],
+    pub program: Program<W>,
+    /// The instruction to run next. The machine halts once it points outside the program.
+    pub ip: usize,
+    /// How many instructions have run.
+    pub steps: u64,
+}
+
+impl<const N: usize, W: Word> Machine<N, W> {
+    pub fn new(program: impl Into<Program<W>>) -> Machine<N, W> {
+        Machine {
+            registers: [W::default(); N],
+            program: program.into(),
+            ip: 0,
+            steps: 0,
+        }
+    }
+
+    pub fn with_registers(mut self, registers: [W; N]) -> Machine<N, W> {
+        self.registers = registers;
+        self
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.ip >= self.program.instructions.len()
+    }
+
+    /// The instruction that runs next, unless the machine has halted.
+    pub fn next_instruction(&self) -> Option<&Instruction<W>> {
+        self.program.instructions.get(self.ip)
+    }
+
+    /// Runs one instruction. A bound register holds the instruction pointer while the instruction
+    /// runs, and the pointer continues from whatever the instruction left there. Returns `false`,
+    /// doing nothing, if the machine has halted.
+    pub fn step(&mut self) -> std::result::Result<bool, RegisterError<W>> {
+        let Some(&instruction) = self.next_instruction() else {
+            return Ok(false);
+        };
+
+        let bound = match self.program.ip_register {
+            Some(register) if register >= N => {
+                return Err(RegisterError {
+                    register: W::from_index(register),
+                    registers: N,
+                })
+            }
+            bound => bound,
+        };
+
+        if let Some(register) = bound {
+            self.registers[register] = W::from_index(self.ip);
+        }
+
+        instruction.execute(&mut self.registers)?;
+
+        if let Some(register) = bound {
+            // A pointer that is no index at all (e.g. negative) is outside the program too.
+            self.ip = self.registers[register].to_index().unwrap_or(usize::MAX);
+        }
+
+        self.ip = self.ip.saturating_add(1);
+        self.steps += 1;
+
+        Ok(true)
+    }
+
+    /// Runs until the machine halts.
+    pub fn run(&mut self) -> std::result::Result<(), RegisterError<W>> {
+        while self.step()? {}
+
+        Ok(())
+    }
+
+    /// Runs at most `max_steps` instructions, for programs that may never halt. Returns whether
+    /// the machine halted.
+    pub fn run_for(&mut self, max_steps: u64) -> std::result::Result<bool, RegisterError<W>> {
+        for _ in 0..max_steps {
+            if !self.step()? {
+                return Ok(true);
+            }
+        }
+
+        Ok(self.is_halted())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseMode;
+
+    #[test]
+    fn runs_until_the_program_ends() {
+        let mut machine = Machine::<4>::new(vec![
+            Instruction::new(Opcode::Seti, [5, 0, 1]),
+            Instruction::new(Opcode::Muli, [1, 3, 2]),
+            Instruction::new(Opcode::Gtri, [2, 10, 3]),
+        ])
+        .with_registers([7, 0, 0, 0]);
+
+        machine.run().unwrap();
+
+        assert!(machine.is_halted());
+        assert_eq!(machine.registers, [7, 5, 15, 1]);
+        assert_eq!(machine.steps, 3);
+        assert_eq!(machine.step(), Ok(false));
+    }
+
+    #[test]
+    fn binds_the_instruction_pointer() {
+        let source = "#ip 0\nseti 5 0 1\nseti 6 0 2\naddi 0 1 0\naddr 1 2 3\nsetr 1 0 0\nseti 8 0 4\nseti 9 0 5\n";
+        let program = Program::parse(source, &mut Parser::new(ParseMode::Strict)).unwrap();
+
+        assert_eq!(program.ip_register, Some(0));
+        assert_eq!(program.to_string(), source);
+
+        let mut machine = Machine::<6>::new(program.clone());
+        assert_eq!(machine.run_for(3), Ok(false));
+        assert_eq!(machine.ip, 4);
+
+        machine.run().unwrap();
+        assert_eq!(machine.registers, [6, 5, 6, 0, 0, 9]);
+        assert_eq!(machine.steps, 5);
+    }
+
+    #[test]
+    fn rejects_unknown_opcodes() {
+        let mut parser = Parser::new(ParseMode::Strict);
+        let program =
+            Program::<Int>::parse("#ip 1\nmovi 1 2 3\naddi 1 2 3\n", &mut parser).unwrap();
+
+        assert_eq!(program.instructions.len(), 1);
+        assert!(parser.finish().is_err());
+        assert!(
+            Program::<Int>::parse("#ip 1\n#ip 2\n", &mut Parser::new(ParseMode::Strict)).is_err()
+        );
+    }
+
+    #[test]
+    fn missing_registers_are_errors() {
+        let mut registers: [Int; 4] = [1, 2, 3, 4];
+
+        assert_eq!(
+            Opcode::Addr.execute(&mut registers, [0, 4, 1]),
+            Err(RegisterError {
+                register: 4,
+                registers: 4
+            })
+        );
+        assert_eq!(registers, [1, 2, 3, 4]);
+        assert!(Opcode::Seti.execute(&mut registers, [9, 0, 6]).is_err());
+
+        let mut machine = Machine::<2>::new(Program {
+            ip_register: Some(2),
+            instructions: vec![Instruction::new(Opcode::Seti, [0, 0, 0])],
+        });
+        assert!(machine.step().is_err());
+    }
+
+    #[test]
+    fn words_wrap() {
+        let mut registers = [u64::MAX, 3];
+        Opcode::Addr.execute(&mut registers, [0, 1, 0]).unwrap();
+        assert_eq!(registers, [2, 3]);
+
+        let mut registers = [-5i64, 0];
+        Opcode::Gtir.execute(&mut registers, [-1, 0, 1]).unwrap();
+        assert_eq!(registers, [-5, 1]);
+        assert!(Opcode::Setr.execute(&mut registers, [-1, 0, 1]).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+//! Programs written by hand, with labels and names for registers:
+//!
+//! ```text
+//! #ip 5
+//! #reg count 2          ; `count` is register 2
+//!         seti 0 0 count
+//! again:  addi count 1 count
+//!         eqri count 10 r1
+//!         addr ip r1 ip
+//!         jmp again       ; seti again-1 0 ip
+//! ```
+//!
+//! Register operands are `rN`, `ip` for the bound register, a `#reg` name or a number. Immediate
+//! operands are numbers or labels, which stand for the address of the instruction after them.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::{Instruction, Mode, Opcode, Program, Word};
+use crate::{Error, ParseMode, Parser, Result};
+
+const COMMENT: char = ';';
+
+fn is_name(word: &str) -> bool {
+    let mut chars = word.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// What the first pass learns: where the labels are and what the registers are called.
+#[derive(Debug, Default)]
+struct Symbols {
+    ip_register: Option<usize>,
+    registers: HashMap<String, usize>,
+    labels: HashMap<String, usize>,
+}
+
+impl Symbols {
+    fn register<W: Word>(&self, operand: &str) -> std::result::Result<W, String> {
+        let register = match operand {
+            "ip" => self
+                .ip_register
+                .ok_or("ip is not bound, add #ip first".to_string())?,
+            _ => match self.registers.get(operand) {
+                Some(&register) => register,
+                None => operand
+                    .strip_prefix('r')
+                    .unwrap_or(operand)
+                    .parse()
+                    .map_err(|_| format!("unknown register {operand}"))?,
+            },
+        };
+
+        Ok(W::from_index(register))
+    }
+
+    fn value<W: Word>(&self, operand: &str) -> std::result::Result<W, String> {
+        match self.labels.get(operand) {
+            Some(&address) => Ok(W::from_index(address)),
+            None if is_name(operand) => Err(format!("unknown label {operand}")),
+            None => operand.parse().map_err(|err| format!("{operand}: {err}")),
+        }
+    }
+
+    /// `jmp label`, which sets the pointer to just before the label.
+    fn jump<W: Word>(&self, target: &str) -> std::result::Result<Instruction<W>, String> {
+        let ip = self
+            .ip_register
+            .ok_or("jmp needs the instruction pointer bound with #ip".to_string())?;
+        let address = *self
+            .labels
+            .get(target)
+            .ok_or_else(|| format!("unknown label {target}"))?;
+        let before = address
+            .checked_sub(1)
+            .ok_or("cannot jump to the first instruction".to_string())?;
+
+        Ok(Instruction::new(
+            Opcode::Seti,
+            [W::from_index(before), W::default(), W::from_index(ip)],
+        ))
+    }
+
+    fn instruction<W: Word>(&self, words: &[&str]) -> std::result::Result<Instruction<W>, String> {
+        let (opcode, operands) = match words {
+            ["jmp", target] => return self.jump(target),
+            [opcode, a, b, c] => (opcode.parse::<Opcode>()?, [*a, *b, *c]),
+            _ => return Err("expected \"opcode a b c\" or \"jmp label\"".to_string()),
+        };
+
+        let [a_mode, b_mode] = opcode.modes();
+        let [a, b, c] = operands;
+
+        let operand = |mode, operand| match mode {
+            Mode::Register => self.register(operand),
+            Mode::Immediate | Mode::Ignored => self.value(operand),
+        };
+
+        Ok(Instruction::new(
+            opcode,
+            [operand(a_mode, a)?, operand(b_mode, b)?, self.register(c)?],
+        ))
+    }
+}
+
+/// A line without its comment, split into an optional label and the rest.
+fn split_line(line: &str) -> (Option<&str>, &str) {
+    let line = line.split(COMMENT).next().unwrap_or_default().trim();
+
+    match line.split_once(':') {
+        Some((label, rest)) => (Some(label.trim()), rest.trim()),
+        None => (None, line),
+    }
+}
+
+impl<W: Word> Program<W> {
+    /// Assembles `source`. There is no lenient mode: leaving out a line would move every label
+    /// after it, so the lines that do not assemble are all rejected together, and a directive
+    /// that does not is an error on its own.
+    pub fn assemble(source: &str) -> Result<Program<W>> {
+        let mut symbols = Symbols::default();
+        let mut address = 0;
+
+        for (i, line) in source.lines().enumerate() {
+            let (label, rest) = split_line(line);
+
+            if let Some(label) = label {
+                if !is_name(label) {
+                    return Err(Error::parse(i, line, format!("invalid label {label}")));
+                }
+
+                if symbols.labels.insert(label.to_string(), address).is_some() {
+                    return Err(Error::parse(
+                        i,
+                        line,
+                        format!("label {label} is defined twice"),
+                    ));
+                }
+            }
+
+            let words: Vec<_> = rest.split_whitespace().collect();
+
+            match words.as_slice() {
+                [] => {}
+                ["#ip", register] => {
+                    if symbols.ip_register.is_some() {
+                        return Err(Error::parse(
+                            i,
+                            line,
+                            "the instruction pointer is already bound",
+                        ));
+                    }
+
+                    let register: W = symbols
+                        .register(register)
+                        .map_err(|err| Error::parse(i, line, err))?;
+                    symbols.ip_register = register.to_index();
+                }
+                ["#reg", name, register] if is_name(name) && *name != "ip" => {
+                    let register = register.parse().map_err(|err| Error::parse(i, line, err))?;
+                    symbols.registers.insert(name.to_string(), register);
+                }
+                [directive, ..] if directive.starts_with('#') => {
+                    return Err(Error::parse(
+                        i,
+                        line,
+                        "expected \"#ip N\" or \"#reg name N\"",
+                    ));
+                }
+                _ => address += 1,
+            }
+        }
+
+        let mut parser = Parser::new(ParseMode::Strict);
+        let mut instructions = vec![];
+
+        for (i, line) in source.lines().enumerate() {
+            let words: Vec<_> = split_line(line).1.split_whitespace().collect();
+
+            if words.is_empty() || words[0].starts_with('#') {
+                continue;
+            }
+
+            instructions.extend(
+                parser.line(
+                    symbols
+                        .instruction(&words)
+                        .map_err(|err| Error::parse(i, line, err)),
+                ),
+            );
+        }
+
+        parser.finish()?;
+
+        Ok(Program {
+            ip_register: symbols.ip_register,
+            instructions,
+        })
+    }
+
+    /// Source that [`Program::assemble`] turns back into this program, with registers named and
+    /// every jump target labelled.
+    pub fn to_assembly(&self) -> String {
+        let targets = self.jump_targets();
+        let mut source = String::new();
+
+        let name = |register: W| match self.ip_register {
+            Some(ip) if register.to_index() == Some(ip) => "ip".to_string(),
+            _ => format!("r{register}"),
+        };
+
+        if let Some(register) = self.ip_register {
+            writeln!(source, "#ip {register}").unwrap();
+        }
+
+        for (pc, instruction) in self.instructions.iter().enumerate() {
+            if targets.contains(&pc) {
+                writeln!(source, "L{pc}:").unwrap();
+            }
+
+            let [a, b] = [instruction.a, instruction.b]
+                .into_iter()
+                .zip(instruction.opcode.modes())
+                .map(|(operand, mode)| match mode {
+                    Mode::Register => name(operand),
+                    Mode::Immediate | Mode::Ignored => operand.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap();
+
+            writeln!(
+                source,
+                "    {} {a} {b} {}",
+                instruction.opcode,
+                name(instruction.c)
+            )
+            .unwrap();
+        }
+
+        source
+    }
+
+    /// The program as the numbers day 16's samples use, where `numbering[n]` is the operation
+    /// numbered `n`. `None` if an operation has no number.
+    pub fn encode(&self, numbering: &[Opcode]) -> Option<Vec<[W; 4]>> {
+        self.instructions
+            .iter()
+            .map(|instruction| instruction.encode(numbering))
+            .collect()
+    }
+}
+
+impl<W: Word> Instruction<W> {
+    /// The instruction as `[number, a, b, c]`, where `numbering[number]` is its operation.
+    pub fn encode(&self, numbering: &[Opcode]) -> Option<[W; 4]> {
+        let number = numbering.iter().position(|&opcode| opcode == self.opcode)?;
+
+        Some([W::from_index(number), self.a, self.b, self.c])
+    }
+
+    /// The inverse of [`Instruction::encode`]. `None` if the number has no operation.
+    pub fn decode([number, a, b, c]: [W; 4], numbering: &[Opcode]) -> Option<Instruction<W>> {
+        let opcode = *numbering.get(number.to_index()?)?;
+
+        Some(Instruction::new(opcode, [a, b, c]))
+    }
+}
@@ -0,0 +1,330 @@
+//! Pseudo-code for programs, recognising the idioms their jumps are made of: a comparison followed
+//! by `addr` into the instruction pointer is a conditional jump, and a conditional jump back to
+//! an earlier label is a loop.
+
+use std::collections::BTreeSet;
+use std::fmt::{self, Display, Formatter};
+
+use super::disassemble::{assignment, label, Expr, Flow};
+use super::{Opcode, Program, Word};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Stmt<W> {
+    Label(usize),
+    Assign(W, Expr<W>),
+    Goto(usize),
+    /// Goes to the instruction when the condition holds.
+    If(Expr<W>, usize),
+    /// Adds the register to the instruction pointer after the instruction at `from`.
+    Skip {
+        from: usize,
+        register: W,
+    },
+    /// Goes to one past the value.
+    ComputedGoto(Expr<W>),
+    /// Runs the statements when the condition holds.
+    Block(Expr<W>, Vec<Stmt<W>>),
+    /// Runs the statements, then again for as long as the condition holds, or forever without one.
+    Loop(Vec<Stmt<W>>, Option<Expr<W>>),
+}
+
+impl<W: Word> Stmt<W> {
+    /// The instructions this statement, or any inside it, may go to.
+    fn targets(&self, targets: &mut Vec<usize>) {
+        match self {
+            Stmt::Goto(target) | Stmt::If(_, target) => targets.push(*target),
+            Stmt::Skip { from, .. } => targets.push(from + 2),
+            Stmt::Block(_, body) | Stmt::Loop(body, _) => {
+                body.iter().for_each(|stmt| stmt.targets(targets))
+            }
+            Stmt::Label(_) | Stmt::Assign(..) | Stmt::ComputedGoto(_) => {}
+        }
+    }
+
+    fn labels(&self, labels: &mut Vec<usize>) {
+        match self {
+            Stmt::Label(pc) => labels.push(*pc),
+            Stmt::Block(_, body) | Stmt::Loop(body, _) => {
+                body.iter().for_each(|stmt| stmt.labels(labels))
+            }
+            _ => {}
+        }
+    }
+}
+
+fn targets<W: Word>(stmts: &[Stmt<W>]) -> Vec<usize> {
+    let mut targets = vec![];
+    stmts.iter().for_each(|stmt| stmt.targets(&mut targets));
+
+    targets
+}
+
+/// Whether `region` of `stmts` only jumps within itself and is only jumped into at its own labels
+/// from within, so it can become the body of a block. The statement at `except` is the jump being
+/// turned into that block.
+fn is_closed<W: Word>(stmts: &[Stmt<W>], region: std::ops::Range<usize>, except: usize) -> bool {
+    let mut labels = vec![];
+    stmts[region.clone()]
+        .iter()
+        .for_each(|stmt| stmt.labels(&mut labels));
+
+    let inside = targets(&stmts[region.clone()]);
+    let outside: Vec<_> = stmts
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !region.contains(i) && *i != except)
+        .flat_map(|(_, stmt)| targets(std::slice::from_ref(stmt)))
+        .collect();
+
+    inside.iter().all(|target| labels.contains(target))
+        && labels.iter().all(|label| !outside.contains(label))
+}
+
+fn position_of_label<W: Word>(stmts: &[Stmt<W>], target: usize) -> Option<usize> {
+    stmts.iter().position(|stmt| *stmt == Stmt::Label(target))
+}
+
+/// Turns one jump of `stmts` into a loop or block, if one can be.
+fn fold_once<W: Word>(stmts: &[Stmt<W>]) -> Option<Vec<Stmt<W>>> {
+    for (j, stmt) in stmts.iter().enumerate() {
+        let (condition, target) = match stmt {
+            Stmt::If(condition, target) => (Some(condition), *target),
+            Stmt::Goto(target) => (None, *target),
+            _ => continue,
+        };
+
+        let Some(i) = position_of_label(stmts, target) else {
+            continue;
+        };
+
+        if i < j {
+            // Back to an earlier label: a loop over everything in between.
+            if !is_closed(stmts, i + 1..j, j) || targets(&stmts[i + 1..j]).contains(&target) {
+                continue;
+            }
+
+            let still_used = stmts
+                .iter()
+                .enumerate()
+                .filter(|&(k, _)| k != j)
+                .any(|(_, stmt)| targets(std::slice::from_ref(stmt)).contains(&target));
+
+            let mut folded = stmts[..i].to_vec();
+
+            if still_used {
+                folded.push(Stmt::Label(target));
+            }
+
+            folded.push(Stmt::Loop(stmts[i + 1..j].to_vec(), condition.cloned()));
+            folded.extend_from_slice(&stmts[j + 1..]);
+
+            return Some(folded);
+        }
+
+        // Forward past some statements: those only run when the condition does not hold.
+        let Some(negated) = condition.and_then(Expr::negated) else {
+            continue;
+        };
+
+        if !is_closed(stmts, j + 1..i, j) {
+            continue;
+        }
+
+        let mut folded = stmts[..j].to_vec();
+        folded.push(Stmt::Block(negated, stmts[j + 1..i].to_vec()));
+        folded.extend_from_slice(&stmts[i..]);
+
+        return Some(folded);
+    }
+
+    None
+}
+
+/// Folds jumps into loops and blocks for as long as there are any to fold, innermost first.
+fn structure<W: Word>(mut stmts: Vec<Stmt<W>>) -> Vec<Stmt<W>> {
+    while let Some(folded) = fold_once(&stmts) {
+        stmts = folded;
+    }
+
+    stmts
+        .into_iter()
+        .map(|stmt| match stmt {
+            Stmt::Block(condition, body) => Stmt::Block(condition, structure(body)),
+            Stmt::Loop(body, condition) => Stmt::Loop(structure(body), condition),
+            stmt => stmt,
+        })
+        .collect()
+}
+
+/// Drops the labels nothing goes to any more.
+fn prune<W: Word>(stmts: Vec<Stmt<W>>, used: &BTreeSet<usize>) -> Vec<Stmt<W>> {
+    stmts
+        .into_iter()
+        .filter(|stmt| !matches!(stmt, Stmt::Label(pc) if !used.contains(pc)))
+        .map(|stmt| match stmt {
+            Stmt::Block(condition, body) => Stmt::Block(condition, prune(body, used)),
+            Stmt::Loop(body, condition) => Stmt::Loop(prune(body, used), condition),
+            stmt => stmt,
+        })
+        .collect()
+}
+
+/// A decompiled program, which displays as pseudo-code.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Pseudocode<'a, W> {
+    pub program: &'a Program<W>,
+    pub stmts: Vec<Stmt<W>>,
+}
+
+impl<W: Word> Program<W> {
+    /// The comparison at `pc`, if it is the first half of a conditional jump.
+    fn conditional_jump(&self, pc: usize, labels: &BTreeSet<usize>) -> Option<Expr<W>> {
+        let instruction = self.instructions.get(pc)?;
+
+        let is_comparison = matches!(
+            instruction.opcode,
+            Opcode::Gtir | Opcode::Gtri | Opcode::Gtrr | Opcode::Eqir | Opcode::Eqri | Opcode::Eqrr
+        );
+
+        let skips = pc + 1 < self.instructions.len()
+            && self.flow(pc + 1) == Flow::Skip(instruction.c)
+            && !labels.contains(&(pc + 1));
+
+        (is_comparison && self.flow(pc) == Flow::Next && skips).then(|| self.expression(pc))
+    }
+
+    pub fn decompile(&self) -> Pseudocode<'_, W> {
+        let labels = self.jump_targets();
+        let mut stmts = vec![];
+        let mut pc = 0;
+
+        while pc < self.instructions.len() {
+            if labels.contains(&pc) {
+                stmts.push(Stmt::Label(pc));
+            }
+
+            if let Some(condition) = self.conditional_jump(pc, &labels) {
+                // The comparison's register is only there to be added to the pointer, so it is
+                // left out. When it holds, the instruction after the `addr` is skipped, which is
+                // usually a jump elsewhere.
+                let jump = match (pc + 2 < self.instructions.len(), labels.contains(&(pc + 2))) {
+                    (true, false) => self.flow(pc + 2),
+                    _ => Flow::Next,
+                };
+
+                match (jump, condition.negated()) {
+                    (Flow::Jump(target), Some(negated)) => {
+                        stmts.push(Stmt::If(negated, target));
+                        pc += 3;
+                    }
+                    _ => {
+                        stmts.push(Stmt::If(condition, pc + 3));
+                        pc += 2;
+                    }
+                }
+
+                continue;
+            }
+
+            stmts.push(match self.flow(pc) {
+                Flow::Next => Stmt::Assign(self.instructions[pc].c, self.expression(pc)),
+                Flow::Jump(target) => Stmt::Goto(target),
+                Flow::Skip(register) => Stmt::Skip { from: pc, register },
+                Flow::Computed => Stmt::ComputedGoto(self.expression(pc)),
+            });
+            pc += 1;
+        }
+
+        let stmts = structure(stmts);
+        let used = targets(&stmts).into_iter().collect();
+
+        Pseudocode {
+            program: self,
+            stmts: prune(stmts, &used),
+        }
+    }
+}
+
+/// `++rN` in place of `rN` in the condition, if the loop ends by counting `rN` up by one and then
+/// checking it.
+fn counted<W: Word>(body: &[Stmt<W>], condition: &Expr<W>) -> Option<String> {
+    let Some(Stmt::Assign(register, Expr::Binary("+", lhs, rhs))) = body.last() else {
+        return None;
+    };
+
+    let counter = Expr::Register(*register);
+
+    if **lhs != counter || **rhs != Expr::Value(W::from_index(1)) {
+        return None;
+    }
+
+    match condition {
+        Expr::Binary(operator, lhs, rhs) if **lhs == counter => {
+            Some(format!("++{lhs} {operator} {rhs}"))
+        }
+        Expr::Binary(operator, lhs, rhs) if **rhs == counter => {
+            Some(format!("{lhs} {operator} ++{rhs}"))
+        }
+        _ => None,
+    }
+}
+
+impl<W: Word> Pseudocode<'_, W> {
+    fn write(&self, f: &mut Formatter<'_>, stmts: &[Stmt<W>], depth: usize) -> fmt::Result {
+        let indent = "    ".repeat(depth);
+
+        for stmt in stmts {
+            match stmt {
+                Stmt::Label(pc) => writeln!(f, "{indent}{}:", label(self.program, *pc))?,
+                Stmt::Assign(register, value) => {
+                    writeln!(f, "{indent}{}", assignment(*register, value))?
+                }
+                Stmt::Goto(target) => match label(self.program, *target).as_str() {
+                    "halt" => writeln!(f, "{indent}halt")?,
+                    target => writeln!(f, "{indent}goto {target}")?,
+                },
+                Stmt::If(condition, target) => writeln!(
+                    f,
+                    "{indent}if {condition} goto {}",
+                    label(self.program, *target)
+                )?,
+                Stmt::Skip { from, register } => {
+                    writeln!(f, "{indent}goto {} + r{register}", from + 1)?
+                }
+                Stmt::ComputedGoto(value) => writeln!(f, "{indent}goto ({value}) + 1")?,
+                Stmt::Block(condition, body) => {
+                    writeln!(f, "{indent}if {condition} {{")?;
+                    self.write(f, body, depth + 1)?;
+                    writeln!(f, "{indent}}}")?;
+                }
+                Stmt::Loop(body, None) => {
+                    writeln!(f, "{indent}loop {{")?;
+                    self.write(f, body, depth + 1)?;
+                    writeln!(f, "{indent}}}")?;
+                }
+                Stmt::Loop(body, Some(condition)) => {
+                    writeln!(f, "{indent}do {{")?;
+
+                    match counted(body, condition) {
+                        Some(condition) => {
+                            self.write(f, &body[..body.len() - 1], depth + 1)?;
+                            writeln!(f, "{indent}}} while {condition}")?;
+                        }
+                        None => {
+                            self.write(f, body, depth + 1)?;
+                            writeln!(f, "{indent}}} while {condition}")?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Word> Display for Pseudocode<'_, W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write(f, &self.stmts, 0)
+    }
+}
@@ -0,0 +1,257 @@
+//! Listings of programs, with jumps through the instruction pointer resolved where they can be.
+
+use std::collections::BTreeSet;
+use std::fmt::{self, Display, Formatter, Write};
+
+use super::{Mode, Opcode, Program, Word};
+
+/// Where control goes after an instruction.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Flow<W> {
+    /// On to the next instruction.
+    Next,
+    /// Always to the given instruction, which halts the machine if it is outside the program.
+    Jump(usize),
+    /// Adds a register to the instruction pointer, so the next instruction is skipped when the
+    /// register holds 1, as it does after a comparison.
+    Skip(W),
+    /// To an instruction only known when the program runs.
+    Computed,
+}
+
+/// A value an instruction computes, with reads of the bound instruction pointer replaced by the
+/// instruction's own address.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Expr<W> {
+    Register(W),
+    Value(W),
+    Binary(&'static str, Box<Expr<W>>, Box<Expr<W>>),
+}
+
+impl<W: Word> Expr<W> {
+    fn binary(operator: &'static str, lhs: Expr<W>, rhs: Expr<W>) -> Expr<W> {
+        Expr::Binary(operator, Box::new(lhs), Box::new(rhs))
+    }
+
+    /// The opposite of a comparison; `None` for anything else.
+    pub fn negated(&self) -> Option<Expr<W>> {
+        let Expr::Binary(operator, lhs, rhs) = self else {
+            return None;
+        };
+
+        let operator = match *operator {
+            ">" => "<=",
+            "<=" => ">",
+            "==" => "!=",
+            "!=" => "==",
+            _ => return None,
+        };
+
+        Some(Expr::Binary(operator, lhs.clone(), rhs.clone()))
+    }
+}
+
+impl<W: Word> Display for Expr<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Register(register) => write!(f, "r{register}"),
+            Expr::Value(value) => write!(f, "{value}"),
+            Expr::Binary(operator, lhs, rhs) => {
+                for (i, operand) in [lhs, rhs].into_iter().enumerate() {
+                    if i == 1 {
+                        write!(f, " {operator} ")?;
+                    }
+
+                    match **operand {
+                        Expr::Binary(..) => write!(f, "({operand})")?,
+                        _ => write!(f, "{operand}")?,
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// `rC = value`, or `rC += value` and the like when the register is also an input.
+pub fn assignment<W: Word>(register: W, value: &Expr<W>) -> String {
+    let target = Expr::Register(register);
+
+    match value {
+        // These operations commute, so the register may be either input.
+        Expr::Binary(operator @ ("+" | "*" | "&" | "|"), lhs, rhs) if **lhs == target => {
+            format!("{target} {operator}= {rhs}")
+        }
+        Expr::Binary(operator @ ("+" | "*" | "&" | "|"), lhs, rhs) if **rhs == target => {
+            format!("{target} {operator}= {lhs}")
+        }
+        _ => format!("{target} = {value}"),
+    }
+}
+
+/// The name of the instruction at `target`, or `halt` for the outside of the program.
+pub fn label<W>(program: &Program<W>, target: usize) -> String {
+    if target < program.instructions.len() {
+        format!("L{target}")
+    } else {
+        "halt".to_string()
+    }
+}
+
+impl<W: Word> Program<W> {
+    fn is_ip(&self, register: W) -> bool {
+        self.ip_register.is_some() && register.to_index() == self.ip_register
+    }
+
+    /// The value the instruction at `pc` computes.
+    pub fn expression(&self, pc: usize) -> Expr<W> {
+        let instruction = self.instructions[pc];
+
+        let [a, b] = [instruction.a, instruction.b]
+            .into_iter()
+            .zip(instruction.opcode.modes())
+            .map(|(operand, mode)| match mode {
+                Mode::Register if self.is_ip(operand) => Expr::Value(W::from_index(pc)),
+                Mode::Register => Expr::Register(operand),
+                Mode::Immediate | Mode::Ignored => Expr::Value(operand),
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
+        let operator = match instruction.opcode {
+            Opcode::Setr | Opcode::Seti => return a,
+            Opcode::Addr | Opcode::Addi => "+",
+            Opcode::Mulr | Opcode::Muli => "*",
+            Opcode::Banr | Opcode::Bani => "&",
+            Opcode::Borr | Opcode::Bori => "|",
+            Opcode::Gtir | Opcode::Gtri | Opcode::Gtrr => ">",
+            Opcode::Eqir | Opcode::Eqri | Opcode::Eqrr => "==",
+        };
+
+        Expr::binary(operator, a, b)
+    }
+
+    /// Where control goes after the instruction at `pc`.
+    pub fn flow(&self, pc: usize) -> Flow<W> {
+        let instruction = self.instructions[pc];
+
+        if !self.is_ip(instruction.c) {
+            return Flow::Next;
+        }
+
+        let reads = instruction.reads();
+        let others: Vec<_> = reads.iter().filter(|&&r| !self.is_ip(r)).collect();
+
+        match (instruction.opcode, others.as_slice()) {
+            (_, []) => {
+                // Only the pointer and immediates go in, so the target is known now. The pointer
+                // is moved to register 0, so that however high its register is, the instruction
+                // runs on a single one.
+                let [a, b] = [instruction.a, instruction.b]
+                    .into_iter()
+                    .zip(instruction.opcode.modes())
+                    .map(|(operand, mode)| match mode {
+                        Mode::Register => W::default(),
+                        Mode::Immediate | Mode::Ignored => operand,
+                    })
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap();
+                let mut registers = [W::from_index(pc)];
+
+                match instruction
+                    .opcode
+                    .execute(&mut registers, [a, b, W::default()])
+                {
+                    Ok(()) => Flow::Jump(
+                        registers[0]
+                            .to_index()
+                            .and_then(|target| target.checked_add(1))
+                            .unwrap_or(usize::MAX),
+                    ),
+                    Err(_) => Flow::Computed,
+                }
+            }
+            (Opcode::Addr, [&register]) if reads.len() == 2 => Flow::Skip(register),
+            _ => Flow::Computed,
+        }
+    }
+
+    /// The instructions jumped to, including the ones a [`Flow::Skip`] may land on.
+    pub fn jump_targets(&self) -> BTreeSet<usize> {
+        (0..self.instructions.len())
+            .filter_map(|pc| match self.flow(pc) {
+                Flow::Jump(target) => Some(target),
+                Flow::Skip(_) => Some(pc + 2),
+                Flow::Next | Flow::Computed => None,
+            })
+            .filter(|&target| target < self.instructions.len())
+            .collect()
+    }
+
+    /// The first instruction of every basic block: the start, every jump target and every
+    /// instruction after a jump.
+    pub fn leaders(&self) -> BTreeSet<usize> {
+        let after_jumps = (0..self.instructions.len())
+            .filter(|&pc| self.flow(pc) != Flow::Next)
+            .map(|pc| pc + 1)
+            .filter(|&pc| pc < self.instructions.len());
+
+        let mut leaders = self.jump_targets();
+        leaders.insert(0);
+        leaders.extend(after_jumps);
+
+        leaders
+    }
+
+    fn annotation(&self, pc: usize) -> String {
+        match self.flow(pc) {
+            Flow::Next => assignment(self.instructions[pc].c, &self.expression(pc)),
+            Flow::Jump(target) => format!("goto {}", label(self, target)),
+            Flow::Skip(register) => format!("goto {} + r{register}", pc + 1),
+            Flow::Computed => format!("goto ({}) + 1", self.expression(pc)),
+        }
+    }
+
+    /// One instruction per line with what it does, basic blocks separated by blank lines and jump
+    /// targets labelled:
+    ///
+    /// ```text
+    /// #ip 3
+    ///    0  addi 3 16 3       goto L17
+    ///
+    /// L1:
+    ///    1  seti 1 0 4        r4 = 1
+    /// ```
+    pub fn disassemble(&self) -> String {
+        let targets = self.jump_targets();
+        let leaders = self.leaders();
+        let mut listing = String::new();
+
+        if let Some(register) = self.ip_register {
+            writeln!(listing, "#ip {register}").unwrap();
+        }
+
+        for (pc, instruction) in self.instructions.iter().enumerate() {
+            if pc > 0 && leaders.contains(&pc) {
+                writeln!(listing).unwrap();
+            }
+
+            if targets.contains(&pc) {
+                writeln!(listing, "{}:", label(self, pc)).unwrap();
+            }
+
+            writeln!(
+                listing,
+                "{pc:>4}  {:<16}  {}",
+                instruction.to_string(),
+                self.annotation(pc)
+            )
+            .unwrap();
+        }
+
+        listing
+    }
+}
@@ -0,0 +1,173 @@
+//! The device manual from day 16: samples of numbered instructions with the registers before and
+//! after them, from which the operation behind each number can be deduced, optionally followed by
+//! three blank lines and a program in numbered form.
+
+use std::iter::repeat_n;
+
+use itertools::Itertools;
+
+use super::{Int, Opcode, OPCODES};
+use crate::{Error, Parser, Result};
+
+/// The device in the samples has 4 registers.
+pub const REGISTERS: usize = 4;
+
+const PROGRAM_SEPARATOR: &str = "\n\n\n\n";
+
+#[derive(Debug, Copy, Clone)]
+pub struct Sample {
+    pub before: [Int; 4],
+    pub instruction: [Int; 4],
+    pub after: [Int; 4],
+}
+
+impl Sample {
+    /// Whether running the sample's instruction as `opcode` gives the registers after it. An
+    /// operation that would read a register the device does not have cannot be the one.
+    pub fn behaves_like(&self, opcode: Opcode) -> bool {
+        let [_, a, b, c] = self.instruction;
+        let mut registers = self.before;
+
+        opcode.execute(&mut registers, [a, b, c]).is_ok() && registers == self.after
+    }
+}
+
+/// Parses the four numbers in the `i`th line, after `prefix` and before `suffix`.
+fn state_to_array(i: usize, line: &str, prefix: &str, suffix: &str) -> Result<[Int; 4]> {
+    let format = || Error::parse(i, line, format!("expected \"{prefix}a b c d{suffix}\""));
+
+    let numbers = line
+        .strip_prefix(prefix)
+        .and_then(|s| s.strip_suffix(suffix))
+        .ok_or_else(format)?
+        .replace(',', " ")
+        .split_whitespace()
+        .map(|s| s.parse::<Int>().map_err(|err| Error::parse(i, line, err)))
+        .collect::<Result<Vec<_>>>()?;
+
+    numbers.try_into().map_err(|_| format())
+}
+
+/// Parses the `i`th line as a numbered instruction, checking that its opcode and the register it
+/// writes exist.
+fn parse_instruction(i: usize, line: &str) -> Result<[Int; 4]> {
+    let instruction = state_to_array(i, line, "", "")?;
+
+    if instruction[0] >= OPCODES.len() {
+        return Err(Error::parse(i, line, "opcode out of range"));
+    }
+
+    if instruction[3] >= REGISTERS {
+        return Err(Error::parse(i, line, "output register out of range"));
+    }
+
+    Ok(instruction)
+}
+
+/// Parses a sample from its block of consecutive non-blank lines.
+fn parse_sample(block: &[(usize, &str)]) -> Result<Sample> {
+    match *block {
+        [(i, before), (j, instruction), (k, after)] => Ok(Sample {
+            before: state_to_array(i, before, "Before: [", "]")?,
+            instruction: parse_instruction(j, instruction)?,
+            after: state_to_array(k, after, "After:  [", "]")?,
+        }),
+        _ => {
+            let (i, line) = block[0];
+
+            Err(Error::parse(
+                i,
+                line,
+                format!("expected a sample of 3 lines, found {}", block.len()),
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Manual {
+    pub samples: Vec<Sample>,
+    /// The program after the samples, if there is one, as `[number, a, b, c]`.
+    pub program: Option<Vec<[Int; 4]>>,
+}
+
+impl Manual {
+    pub fn parse(input: &str, parser: &mut Parser) -> Result<Manual> {
+        let (sample_str, program_str) = match input.split_once(PROGRAM_SEPARATOR) {
+            Some((samples, program)) => (samples, Some(program)),
+            None => (input, None),
+        };
+
+        let mut samples = vec![];
+        let blocks = sample_str
+            .lines()
+            .enumerate()
+            .group_by(|(_, line)| line.trim().is_empty());
+
+        for (_, block) in blocks.into_iter().filter(|(blank, _)| !blank) {
+            samples.extend(parser.line(parse_sample(&block.collect_vec())));
+        }
+
+        // The program starts after the last sample line and three blank lines.
+        let offset = sample_str.lines().count() + 3;
+
+        let program = program_str.map(|program_str| {
+            program_str
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .filter_map(|(i, line)| parser.line(parse_instruction(offset + i, line)))
+                .collect()
+        });
+
+        Ok(Manual { samples, program })
+    }
+}
+
+/// The operation behind each number, `numbering[n]` being the one numbered `n`, as the samples
+/// narrow them down.
+pub fn deduce_opcode_name(samples: &[Sample]) -> Result<[Opcode; 16]> {
+    let mut possible_opcodes = repeat_n(Vec::from(OPCODES), 16).collect_vec();
+
+    for sample in samples {
+        possible_opcodes[sample.instruction[0]].retain(|&opcode| sample.behaves_like(opcode));
+    }
+
+    loop {
+        if let Some(opcode) = possible_opcodes.iter().position(Vec::is_empty) {
+            return Err(Error::NoSolution(format!(
+                "no operation matches every sample of opcode {opcode}"
+            )));
+        }
+
+        if possible_opcodes.iter().all(|vec| vec.len() == 1) {
+            break;
+        }
+
+        let known = possible_opcodes
+            .iter()
+            .filter(|vec| vec.len() == 1)
+            .map(|vec| vec[0])
+            .collect_vec();
+        let mut progress = false;
+
+        for x in possible_opcodes.iter_mut().filter(|x| x.len() > 1) {
+            let len = x.len();
+            x.retain(|v| !known.contains(v));
+            progress |= x.len() != len;
+        }
+
+        if !progress {
+            return Err(Error::NoSolution(
+                "the samples do not narrow every opcode down to one operation".to_string(),
+            ));
+        }
+    }
+
+    Ok(possible_opcodes
+        .iter()
+        .map(|v| v[0])
+        .collect_vec()
+        .try_into()
+        .unwrap())
+}
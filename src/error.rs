@@ -0,0 +1,69 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Longest offending text quoted in a parse error before it is shortened.
+const MAX_TEXT_LEN: usize = 60;
+
+/// Why a day could not parse its input or solve a part.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// A line of the input does not have the expected format. `line` is 1-based.
+    Parse {
+        line: usize,
+        text: String,
+        reason: String,
+    },
+    /// Lines rejected in strict mode, one `Parse` error each.
+    Rejected(Vec<Error>),
+    /// Every line parses, but the input as a whole is inconsistent (e.g. truncated).
+    Invalid(String),
+    /// The input is valid, but has no answer (e.g. no two carts ever collide).
+    NoSolution(String),
+    /// The input is valid, but beyond what the solution supports (e.g. a grid larger than its
+    /// fixed-size array).
+    OutOfRange(String),
+    /// The part has no solution written yet, e.g. in a newly scaffolded day.
+    Unsolved,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// A parse error for the `index`th (0-based) line of the input.
+    pub fn parse(index: usize, text: &str, reason: impl Display) -> Error {
+        Error::Parse {
+            line: index + 1,
+            text: text.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse { line, text, reason } => {
+                if text.chars().count() > MAX_TEXT_LEN {
+                    let text: String = text.chars().take(MAX_TEXT_LEN).collect();
+                    write!(f, "line {line}: {reason}: \"{text}...\"")
+                } else {
+                    write!(f, "line {line}: {reason}: \"{text}\"")
+                }
+            }
+            Error::Rejected(errors) => {
+                write!(f, "{} malformed lines", errors.len())?;
+
+                for err in errors {
+                    write!(f, "\n  {err}")?;
+                }
+
+                Ok(())
+            }
+            Error::Invalid(message) => write!(f, "invalid input: {message}"),
+            Error::NoSolution(message) => write!(f, "no solution found: {message}"),
+            Error::OutOfRange(message) => write!(f, "input out of supported range: {message}"),
+            Error::Unsolved => write!(f, "not solved yet"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
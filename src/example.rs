@@ -0,0 +1,99 @@
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::toml_file::{self, StateError};
+use crate::{Error, ParseMode, Solution};
+
+/// Expected answers for both parts of a day: either for the day's `ex.txt`, read from the `ex.toml`
+/// next to it, or for the puzzle input as one entry of `answers.toml`.
+///
+/// Only parts with an expected answer are run against the example, as some puzzles (e.g. day 16
+/// part 2) have no meaningful answer for the example input.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Expected {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part2: Option<String>,
+}
+
+impl Expected {
+    /// Reads `ex.toml` at `path`; a missing file expects nothing.
+    pub fn load(path: &Path) -> Result<Expected, StateError> {
+        toml_file::load(path)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PartCheck {
+    pub answer: Result<String, Error>,
+    pub expected: String,
+    pub elapsed: Duration,
+}
+
+impl PartCheck {
+    pub fn passed(&self) -> bool {
+        self.answer.as_ref() == Ok(&self.expected)
+    }
+}
+
+impl Display for PartCheck {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.answer {
+            Ok(answer) if self.passed() => write!(f, "PASS {answer}"),
+            Ok(answer) => write!(f, "FAIL {answer} (expected {})", self.expected),
+            Err(err) => write!(f, "FAIL {err} (expected {})", self.expected),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExampleReport {
+    pub part1: Option<PartCheck>,
+    pub part2: Option<PartCheck>,
+    pub input_elapsed: Duration,
+}
+
+impl ExampleReport {
+    /// `false` if any checked part failed; parts without an expected answer do not count.
+    pub fn passed(&self) -> bool {
+        [&self.part1, &self.part2]
+            .into_iter()
+            .flatten()
+            .all(PartCheck::passed)
+    }
+}
+
+fn check_part<T: Display>(
+    expected: &Option<String>,
+    part: impl FnOnce() -> Result<T, Error>,
+) -> Option<PartCheck> {
+    let expected = expected.clone()?;
+
+    let now = Instant::now();
+    let answer = part().map(|answer| answer.to_string());
+    let elapsed = now.elapsed();
+
+    Some(PartCheck {
+        answer,
+        expected,
+        elapsed,
+    })
+}
+
+/// Parses `raw` and runs each part that has an expected answer. Examples are always parsed
+/// strictly: a skipped line would make the comparison meaningless.
+pub fn check<S: Solution>(raw: &str, expected: &Expected) -> Result<ExampleReport, Error> {
+    let now = Instant::now();
+    let (input, _) = crate::parse::<S>(raw, ParseMode::Strict)?;
+    let input_elapsed = now.elapsed();
+
+    Ok(ExampleReport {
+        part1: check_part(&expected.part1, || S::part1(input.clone())),
+        part2: check_part(&expected.part2, || S::part2(input)),
+        input_elapsed,
+    })
+}
@@ -0,0 +1,250 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use crate::example::Expected;
+use crate::toml_file::StateError;
+use crate::DEFAULT_YEAR;
+
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+
+/// The first Advent of Code.
+const FIRST_YEAR: u16 = 2015;
+
+const YEAR_FLAG: &str = "--year";
+const INPUT_DIR_FLAG: &str = "--input-dir";
+const INPUT_FLAG: &str = "--input";
+const EXAMPLE_FLAG: &str = "--example";
+
+/// Where to read a day's puzzle input from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    /// `input.txt`, or `ex.txt` for the example, in the day's directory under `dir` (see
+    /// [`day_dir`]). `dir` defaults to `$AOC_INPUT_DIR`, then to the repository's `src/bin`.
+    Day {
+        dir: Option<PathBuf>,
+        example: bool,
+    },
+    File(PathBuf),
+    Stdin,
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        InputSource::Day {
+            dir: None,
+            example: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    NotFound {
+        day: u8,
+        path: PathBuf,
+    },
+    Io {
+        day: u8,
+        path: PathBuf,
+        source: io::Error,
+    },
+    Stdin(io::Error),
+    /// The example's `ex.toml` cannot be read.
+    Expected(StateError),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::NotFound { day, path } => {
+                write!(f, "Day {day:02}: input file {} not found", path.display())
+            }
+            InputError::Io { day, path, source } => {
+                write!(f, "Day {day:02}: cannot read {}: {source}", path.display())
+            }
+            InputError::Stdin(source) => write!(f, "Cannot read input from stdin: {source}"),
+            InputError::Expected(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl Error for InputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            InputError::Io { source, .. } | InputError::Stdin(source) => Some(source),
+            InputError::Expected(err) => Some(err),
+            InputError::NotFound { .. } => None,
+        }
+    }
+}
+
+/// A command-line argument the input flags cannot make sense of.
+#[derive(Debug)]
+pub enum ArgError {
+    MissingValue(&'static str),
+    /// `--year` given something that is not a year at all.
+    InvalidYear(String),
+    /// `--year` before the first event.
+    YearTooEarly(u16),
+}
+
+impl Display for ArgError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgError::MissingValue(flag) => write!(f, "{flag} requires a value"),
+            ArgError::InvalidYear(year) => write!(f, "Invalid year {year}, expected a number"),
+            ArgError::YearTooEarly(year) => {
+                write!(f, "There is no {year} event, the first was in {FIRST_YEAR}")
+            }
+        }
+    }
+}
+
+impl Error for ArgError {}
+
+/// Where a day's solution and inputs live under `dir`: `dayNN` for the repository's own year, so
+/// the 2018 days keep their place, and `<year>/dayNN` for any other event.
+pub fn day_dir(dir: &Path, year: u16, day: u8) -> PathBuf {
+    let day_dir = format!("day{day:02}");
+
+    if year == DEFAULT_YEAR {
+        dir.join(day_dir)
+    } else {
+        dir.join(year.to_string()).join(day_dir)
+    }
+}
+
+/// Takes `--year <year>` out of `args`, defaulting to the repository's own year.
+pub fn year_from_args(args: &mut Vec<String>) -> Result<u16, ArgError> {
+    let Some(i) = args.iter().position(|arg| arg == YEAR_FLAG) else {
+        return Ok(DEFAULT_YEAR);
+    };
+
+    args.remove(i);
+
+    if i == args.len() {
+        return Err(ArgError::MissingValue(YEAR_FLAG));
+    }
+
+    let year = args.remove(i);
+
+    match year.parse() {
+        Ok(year) if year >= FIRST_YEAR => Ok(year),
+        Ok(year) => Err(ArgError::YearTooEarly(year)),
+        Err(_) => Err(ArgError::InvalidYear(year)),
+    }
+}
+
+fn default_dir() -> PathBuf {
+    env::var_os(INPUT_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("src/bin"))
+}
+
+impl InputSource {
+    /// Takes `--input-dir <dir>`, `--example`, `--input <path>` and `-` (stdin) out of `args`,
+    /// leaving every other argument.
+    pub fn from_args(args: &mut Vec<String>) -> Result<InputSource, ArgError> {
+        let mut dir = None;
+        let mut example = false;
+        let mut file = None;
+        let mut stdin = false;
+
+        let mut remaining = Vec::with_capacity(args.len());
+        let mut iter = args.drain(..);
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-" => stdin = true,
+                EXAMPLE_FLAG => example = true,
+                INPUT_DIR_FLAG => {
+                    dir = Some(
+                        iter.next()
+                            .ok_or(ArgError::MissingValue(INPUT_DIR_FLAG))?
+                            .into(),
+                    );
+                }
+                INPUT_FLAG => {
+                    file = Some(
+                        iter.next()
+                            .ok_or(ArgError::MissingValue(INPUT_FLAG))?
+                            .into(),
+                    );
+                }
+                _ => remaining.push(arg),
+            }
+        }
+
+        drop(iter);
+        *args = remaining;
+
+        Ok(if stdin {
+            InputSource::Stdin
+        } else if let Some(file) = file {
+            InputSource::File(file)
+        } else {
+            InputSource::Day { dir, example }
+        })
+    }
+
+    /// Whether this reads the day's actual puzzle input, as opposed to an example or a custom file.
+    pub fn is_puzzle_input(&self) -> bool {
+        matches!(self, InputSource::Day { example: false, .. })
+    }
+
+    fn day_dir(dir: &Option<PathBuf>, year: u16, day: u8) -> PathBuf {
+        day_dir(&dir.clone().unwrap_or_else(default_dir), year, day)
+    }
+
+    /// The file that would be read for `day` of `year`, or `None` when reading from stdin.
+    pub fn path(&self, year: u16, day: u8) -> Option<PathBuf> {
+        match self {
+            InputSource::Day { dir, example } => {
+                let file_name = if *example { "ex.txt" } else { "input.txt" };
+
+                Some(Self::day_dir(dir, year, day).join(file_name))
+            }
+            InputSource::File(path) => Some(path.clone()),
+            InputSource::Stdin => None,
+        }
+    }
+
+    /// The file name to show in messages about `day`'s input.
+    pub fn describe(&self, year: u16, day: u8) -> String {
+        self.path(year, day)
+            .map_or("<stdin>".to_string(), |path| path.display().to_string())
+    }
+
+    pub fn load(&self, year: u16, day: u8) -> Result<String, InputError> {
+        let Some(path) = self.path(year, day) else {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(InputError::Stdin)?;
+
+            return Ok(input);
+        };
+
+        fs::read_to_string(&path).map_err(|source| {
+            if source.kind() == io::ErrorKind::NotFound {
+                InputError::NotFound { day, path }
+            } else {
+                InputError::Io { day, path, source }
+            }
+        })
+    }
+
+    /// The expected answers from `ex.toml`, when running the example.
+    pub fn expected(&self, year: u16, day: u8) -> Result<Option<Expected>, InputError> {
+        let InputSource::Day { dir, example: true } = self else {
+            return Ok(None);
+        };
+
+        Expected::load(&Self::day_dir(dir, year, day).join("ex.toml"))
+            .map(Some)
+            .map_err(InputError::Expected)
+    }
+}
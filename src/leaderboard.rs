@@ -0,0 +1,253 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::{Duration, SystemTime};
+
+use serde::Deserialize;
+
+use crate::client::unlock_time;
+use crate::date;
+
+/// The site asks that a private leaderboard is fetched at most once every 15 minutes.
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Star {
+    pub get_star_ts: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    /// `None` for members who have not set a public name.
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u32,
+    #[serde(default)]
+    pub last_star_ts: u64,
+    /// Day, then part, to when the star was earned.
+    #[serde(default)]
+    pub completion_day_level: BTreeMap<u8, BTreeMap<u8, Star>>,
+}
+
+impl Member {
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("(anonymous user #{})", self.id))
+    }
+
+    pub fn star(&self, day: u8, part: u8) -> Option<SystemTime> {
+        let star = self.completion_day_level.get(&day)?.get(&part)?;
+
+        Some(date::from_unix_seconds(star.get_star_ts))
+    }
+
+    /// How long part 2 took after part 1.
+    pub fn delta(&self, day: u8) -> Option<Duration> {
+        self.star(day, 2)?.duration_since(self.star(day, 1)?).ok()
+    }
+}
+
+/// A private leaderboard as the site's JSON API returns it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Leaderboard {
+    pub owner_id: u64,
+    pub event: String,
+    pub members: BTreeMap<String, Member>,
+}
+
+/// `HH:MM:SS`, with hours going past 24 so that durations stay comparable at a glance.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+impl Leaderboard {
+    pub fn from_json(json: &str) -> serde_json::Result<Leaderboard> {
+        serde_json::from_str(json)
+    }
+
+    /// Members by local score, then stars, then who got their last star first, as the site
+    /// orders them.
+    pub fn ranked(&self) -> Vec<&Member> {
+        let mut members: Vec<_> = self.members.values().collect();
+        members.sort_by_key(|member| {
+            (
+                Reverse(member.local_score),
+                Reverse(member.stars),
+                member.last_star_ts,
+            )
+        });
+
+        members
+    }
+
+    /// The last day anyone has a star for.
+    pub fn last_day(&self) -> u8 {
+        self.members
+            .values()
+            .flat_map(|member| member.completion_day_level.keys())
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// One row per member with their score and a column per day: `*` for both stars, `+` for
+    /// part 1 only.
+    pub fn ranking(&self) -> String {
+        let days = 1..=self.last_day();
+        let name_width = self
+            .members
+            .values()
+            .map(|member| member.display_name().len())
+            .max()
+            .unwrap_or(0)
+            .max("Name".len());
+
+        let tens: String = days.clone().map(|day| (b'0' + day / 10) as char).collect();
+        let units: String = days.clone().map(|day| (b'0' + day % 10) as char).collect();
+
+        let mut report = String::new();
+        writeln!(
+            report,
+            "{:>4} {:>5} {:>5} {:name_width$} {tens}",
+            "", "", "", ""
+        )
+        .unwrap();
+        writeln!(
+            report,
+            "{:>4} {:>5} {:>5} {:<name_width$} {units}",
+            "Rank", "Score", "Stars", "Name"
+        )
+        .unwrap();
+
+        for (rank, member) in self.ranked().into_iter().enumerate() {
+            let stars: String = days
+                .clone()
+                .map(|day| match (member.star(day, 1), member.star(day, 2)) {
+                    (Some(_), Some(_)) => '*',
+                    (Some(_), None) => '+',
+                    _ => '.',
+                })
+                .collect();
+
+            writeln!(
+                report,
+                "{:>4} {:>5} {:>5} {:<name_width$} {stars}",
+                rank + 1,
+                member.local_score,
+                member.stars,
+                member.display_name()
+            )
+            .unwrap();
+        }
+
+        report
+    }
+
+    /// For each day, how long everyone who has both stars took from part 1 to part 2, quickest
+    /// first. Part times count from when the puzzle unlocked.
+    pub fn deltas(&self, year: u16) -> String {
+        let mut report = String::new();
+
+        for day in 1..=self.last_day() {
+            let unlock = unlock_time(year, day);
+            let since_unlock =
+                |time: SystemTime| format_duration(time.duration_since(unlock).unwrap_or_default());
+
+            let mut finished: Vec<_> = self
+                .members
+                .values()
+                .filter_map(|member| {
+                    Some((
+                        member.delta(day)?,
+                        member.star(day, 1)?,
+                        member.star(day, 2)?,
+                        member.display_name(),
+                    ))
+                })
+                .collect();
+
+            if finished.is_empty() {
+                continue;
+            }
+
+            finished.sort();
+
+            if report.is_empty() {
+                writeln!(
+                    report,
+                    "  {:>9} {:>9} {:>9}  Name",
+                    "Part 1", "Part 2", "Delta"
+                )
+                .unwrap();
+            }
+
+            writeln!(report, "Day {day:02}").unwrap();
+
+            for (delta, part1, part2, name) in finished {
+                writeln!(
+                    report,
+                    "  {:>9} {:>9} {:>9}  {name}",
+                    since_unlock(part1),
+                    since_unlock(part2),
+                    format!("+{}", format_duration(delta))
+                )
+                .unwrap();
+            }
+        }
+
+        report
+    }
+
+    /// Every member's stars in the order they were earned, in ranking order.
+    pub fn timelines(&self, year: u16) -> String {
+        let mut report = String::new();
+
+        for member in self.ranked() {
+            writeln!(
+                report,
+                "{} ({} points, {} stars)",
+                member.display_name(),
+                member.local_score,
+                member.stars
+            )
+            .unwrap();
+
+            let mut stars: Vec<_> = member
+                .completion_day_level
+                .iter()
+                .flat_map(|(&day, parts)| {
+                    parts
+                        .iter()
+                        .map(move |(&part, star)| (star.get_star_ts, day, part))
+                })
+                .collect();
+            stars.sort();
+
+            for (ts, day, part) in stars {
+                let time = date::from_unix_seconds(ts);
+                let after_unlock = time
+                    .duration_since(unlock_time(year, day))
+                    .unwrap_or_default();
+
+                writeln!(
+                    report,
+                    "  {}  day {day:02} part {part}  {} after unlock",
+                    date::format_time(time),
+                    format_duration(after_unlock)
+                )
+                .unwrap();
+            }
+        }
+
+        report
+    }
+}
@@ -0,0 +1,240 @@
+use std::env;
+use std::fmt::Display;
+use std::process::exit;
+use std::time::{Duration, Instant};
+
+extern crate self as advent_of_code_2018;
+
+pub mod answers;
+pub mod bench;
+pub mod client;
+pub mod date;
+pub mod days;
+pub mod elfcode;
+pub mod error;
+pub mod example;
+pub mod input;
+pub mod leaderboard;
+pub mod parser;
+pub mod puzzle;
+pub mod rate_limit;
+pub mod report;
+pub mod session;
+pub mod setup;
+pub mod submit;
+pub mod toml_file;
+
+pub use answers::{AnswerRegistry, Verdict};
+pub use error::{Error, Result};
+pub use example::{ExampleReport, Expected};
+pub use input::{day_dir, ArgError, InputError, InputSource};
+pub use parser::{ParseMode, Parser};
+
+/// The event this repository was started for. Its days live directly in `src/bin`; days of other
+/// events are kept apart in a directory per year.
+pub const DEFAULT_YEAR: u16 = 2018;
+
+/// A single day's puzzle: how to turn the raw input into `Input`, and how to solve both parts.
+pub trait Solution {
+    const YEAR: u16 = DEFAULT_YEAR;
+    const DAY: u8;
+
+    type Input: Clone;
+    type Output1: Display;
+    type Output2: Display;
+
+    /// Lines that do not parse but can be left out without making the rest meaningless go through
+    /// [`Parser::line`]; anything else is returned as an error.
+    fn parse(input: &str, parser: &mut Parser) -> Result<Self::Input>;
+
+    fn part1(input: Self::Input) -> Result<Self::Output1>;
+
+    fn part2(input: Self::Input) -> Result<Self::Output2>;
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Timings {
+    pub input: Duration,
+    pub part1: Duration,
+    pub part2: Duration,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.input + self.part1 + self.part2
+    }
+}
+
+/// The outcome of both parts; one part failing does not stop the other from running.
+pub struct Answers<S: Solution> {
+    pub part1: Result<S::Output1>,
+    pub part2: Result<S::Output2>,
+    /// Lines left out of the input in lenient mode.
+    pub skipped: usize,
+    pub timings: Timings,
+}
+
+/// Both answers rendered with `Display`, for callers that handle many days at once.
+pub struct DayAnswers {
+    pub part1: Result<String>,
+    pub part2: Result<String>,
+    pub skipped: usize,
+    pub timings: Timings,
+}
+
+/// Parses `raw` in the given mode, returning the input and the number of skipped lines.
+pub fn parse<S: Solution>(raw: &str, mode: ParseMode) -> Result<(S::Input, usize)> {
+    let mut parser = Parser::new(mode);
+    let input = S::parse(raw, &mut parser);
+
+    // In strict mode, the rejected lines explain the failure better than whatever they led to.
+    let skipped = parser.finish()?;
+
+    Ok((input?, skipped))
+}
+
+/// Parses `raw` and solves both parts, timing each step. Fails only if the input does not parse.
+pub fn solve<S: Solution>(raw: &str, mode: ParseMode) -> Result<Answers<S>> {
+    let mut now = Instant::now();
+    let (input, skipped) = parse::<S>(raw, mode)?;
+    let input_elapsed = now.elapsed();
+
+    now = Instant::now();
+    let part1 = S::part1(input.clone());
+    let part1_elapsed = now.elapsed();
+
+    now = Instant::now();
+    let part2 = S::part2(input);
+    let part2_elapsed = now.elapsed();
+
+    Ok(Answers {
+        part1,
+        part2,
+        skipped,
+        timings: Timings {
+            input: input_elapsed,
+            part1: part1_elapsed,
+            part2: part2_elapsed,
+        },
+    })
+}
+
+pub fn solve_erased<S: Solution>(raw: &str, mode: ParseMode) -> Result<DayAnswers> {
+    let answers = solve::<S>(raw, mode)?;
+
+    Ok(DayAnswers {
+        part1: answers.part1.map(|answer| answer.to_string()),
+        part2: answers.part2.map(|answer| answer.to_string()),
+        skipped: answers.skipped,
+        timings: answers.timings,
+    })
+}
+
+fn exit_with(err: impl Display) -> ! {
+    eprintln!("{err}");
+    exit(1)
+}
+
+fn print_example_report(day: u8, report: &ExampleReport) {
+    println!("--- Day {day:02} (example) ---");
+
+    for (part, check) in [(1, &report.part1), (2, &report.part2)] {
+        match check {
+            Some(check) => println!("Part {part}: {check}"),
+            None => println!("Part {part}: no expected answer in ex.toml"),
+        }
+    }
+
+    println!("Reading input took: {:.2?}", report.input_elapsed);
+
+    for (part, check) in [(1, &report.part1), (2, &report.part2)] {
+        if let Some(check) = check {
+            println!("Part {part} took: {:.2?}", check.elapsed);
+        }
+    }
+}
+
+/// Reads the day's input (see [`InputSource::from_args`] for the accepted arguments), solves both
+/// parts and prints the answers with their timings. Malformed lines are skipped and counted unless
+/// `--strict` is given.
+///
+/// Exits with a failure status if the input cannot be read or parsed, if a part fails, or if the
+/// example does not match `ex.toml`.
+pub fn run<S: Solution>() {
+    let mut args: Vec<_> = env::args().skip(1).collect();
+    let source = InputSource::from_args(&mut args).unwrap_or_else(|err| exit_with(err));
+    let mode = ParseMode::from_args(&mut args);
+
+    let now = Instant::now();
+    let raw = source
+        .load(S::YEAR, S::DAY)
+        .unwrap_or_else(|err| exit_with(err));
+    let read_elapsed = now.elapsed();
+
+    let invalid_input = |err: Error| {
+        format!(
+            "Day {:02}: {}: {err}",
+            S::DAY,
+            source.describe(S::YEAR, S::DAY)
+        )
+    };
+
+    if let Some(expected) = source
+        .expected(S::YEAR, S::DAY)
+        .unwrap_or_else(|err| exit_with(err))
+    {
+        let report = example::check::<S>(&raw, &expected)
+            .unwrap_or_else(|err| exit_with(invalid_input(err)));
+        print_example_report(S::DAY, &report);
+
+        exit(if report.passed() { 0 } else { 1 });
+    }
+
+    let mut answers = solve::<S>(&raw, mode).unwrap_or_else(|err| exit_with(invalid_input(err)));
+    answers.timings.input += read_elapsed;
+
+    println!("--- Day {:02} ---", S::DAY);
+
+    if answers.skipped > 0 {
+        println!(
+            "Skipped {} malformed lines (run with --strict to list them)",
+            answers.skipped
+        );
+    }
+
+    let registry = if source.is_puzzle_input() {
+        AnswerRegistry::load(&answers::default_path(S::YEAR))
+            .map_err(|err| eprintln!("{err}"))
+            .ok()
+    } else {
+        None
+    };
+
+    let mut failed = false;
+
+    for (part, answer) in [
+        (1, answers.part1.map(|answer| answer.to_string())),
+        (2, answers.part2.map(|answer| answer.to_string())),
+    ] {
+        match (answer, &registry) {
+            (Err(err), _) => {
+                // A part not solved yet is missing, like a day not implemented, not a failure.
+                failed |= err != Error::Unsolved;
+                println!("Part {part}: {err}");
+            }
+            (Ok(answer), Some(registry)) => println!(
+                "Part {part}: {answer} ({})",
+                registry.verdict(S::DAY, part, &answer)
+            ),
+            (Ok(answer), None) => println!("Part {part}: {answer}"),
+        }
+    }
+
+    println!("Reading input took: {:.2?}", answers.timings.input);
+    println!("Part 1 took: {:.2?}", answers.timings.part1);
+    println!("Part 2 took: {:.2?}", answers.timings.part2);
+
+    if failed {
+        exit(1);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{Error, Result};
+
+const STRICT_FLAG: &str = "--strict";
+
+/// What to do with input lines that do not parse.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Every line must parse; the input is rejected with a list of all the lines that do not.
+    Strict,
+    /// Lines that do not parse are skipped and counted.
+    Lenient,
+}
+
+impl ParseMode {
+    /// Takes `--strict` out of `args`. The runners are lenient unless asked otherwise.
+    pub fn from_args(args: &mut Vec<String>) -> ParseMode {
+        let len = args.len();
+        args.retain(|arg| arg != STRICT_FLAG);
+
+        if args.len() != len {
+            ParseMode::Strict
+        } else {
+            ParseMode::Lenient
+        }
+    }
+}
+
+/// Collects the lines a day could not parse, so that one bad line does not stop the others from
+/// being checked.
+#[derive(Debug, Clone)]
+pub struct Parser {
+    mode: ParseMode,
+    rejected: Vec<Error>,
+}
+
+impl Parser {
+    pub fn new(mode: ParseMode) -> Parser {
+        Parser {
+            mode,
+            rejected: vec![],
+        }
+    }
+
+    pub fn mode(&self) -> ParseMode {
+        self.mode
+    }
+
+    /// Passes a parsed line through, or records why it did not parse and drops it.
+    pub fn line<T>(&mut self, result: Result<T>) -> Option<T> {
+        result.map_err(|err| self.reject(err)).ok()
+    }
+
+    /// Records a line the caller has decided to leave out.
+    pub fn reject(&mut self, err: Error) {
+        self.rejected.push(err);
+    }
+
+    /// The number of lines that were skipped, or, in strict mode, every rejected line as an error.
+    pub fn finish(self) -> Result<usize> {
+        match self.mode {
+            ParseMode::Strict if !self.rejected.is_empty() => Err(Error::Rejected(self.rejected)),
+            _ => Ok(self.rejected.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_all(mode: ParseMode) -> (Vec<i32>, Result<usize>) {
+        let mut parser = Parser::new(mode);
+        let numbers = "1\nx\n3\ny"
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                parser.line(line.parse().map_err(|err| Error::parse(i, line, err)))
+            })
+            .collect();
+
+        (numbers, parser.finish())
+    }
+
+    #[test]
+    fn lenient_skips_and_counts() {
+        assert_eq!(parse_all(ParseMode::Lenient), (vec![1, 3], Ok(2)));
+    }
+
+    #[test]
+    fn strict_lists_every_rejected_line() {
+        let Err(Error::Rejected(rejected)) = parse_all(ParseMode::Strict).1 else {
+            panic!("expected the input to be rejected");
+        };
+
+        let lines: Vec<_> = rejected
+            .iter()
+            .map(|err| match err {
+                Error::Parse { line, .. } => *line,
+                _ => 0,
+            })
+            .collect();
+
+        assert_eq!(lines, [2, 4]);
+    }
+}
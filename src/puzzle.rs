@@ -0,0 +1,268 @@
+/// The heading the site gives the second part of every puzzle.
+const PART_TWO_HEADING: &str = "--- Part Two ---";
+
+const ARTICLE_START: &str = "<article class=\"day-desc\">";
+const ARTICLE_END: &str = "</article>";
+
+const PRE_START: &str = "<pre><code>";
+const PRE_END: &str = "</code></pre>";
+
+/// A puzzle statement, one Markdown section per part that is unlocked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Description {
+    pub parts: Vec<String>,
+}
+
+impl Description {
+    /// Converts the `<article class="day-desc">` sections of a puzzle page. Links starting with `/`
+    /// are made absolute with `base_url`. `None` if the page has no such section.
+    pub fn from_html(html: &str, base_url: &str) -> Option<Description> {
+        let parts: Vec<_> = articles(html)
+            .into_iter()
+            .map(|article| to_markdown(article, base_url))
+            .collect();
+
+        (!parts.is_empty()).then_some(Description { parts })
+    }
+
+    pub fn has_part2(&self) -> bool {
+        self.parts.len() > 1
+    }
+
+    pub fn to_markdown(&self) -> String {
+        self.parts.join("\n")
+    }
+}
+
+/// A `<pre><code>` block from the statement, which may be an example input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    pub part: u8,
+    pub text: String,
+    /// Whether the paragraph before the block talks about an example.
+    pub introduced_as_example: bool,
+}
+
+/// The `<article class="day-desc">` sections of a puzzle page, as HTML. An unclosed section is left
+/// out.
+fn articles(html: &str) -> Vec<&str> {
+    let mut articles = vec![];
+    let mut rest = html;
+
+    while let Some(start) = rest.find(ARTICLE_START) {
+        rest = &rest[start + ARTICLE_START.len()..];
+
+        let Some(end) = rest.find(ARTICLE_END) else {
+            break;
+        };
+
+        articles.push(&rest[..end]);
+        rest = &rest[end + ARTICLE_END.len()..];
+    }
+
+    articles
+}
+
+/// Every `<pre><code>` block in the statement, in order, with the rest of its section after it.
+fn blocks_with_rest(html: &str) -> Vec<(CodeBlock, &str)> {
+    let mut blocks = vec![];
+
+    for (part, article) in (1..).zip(articles(html)) {
+        let mut offset = 0;
+
+        while let Some(start) = article[offset..].find(PRE_START).map(|i| offset + i) {
+            let text_start = start + PRE_START.len();
+
+            let Some(end) = article[text_start..].find(PRE_END).map(|i| text_start + i) else {
+                break;
+            };
+
+            let before = &article[..start];
+            let paragraph = &before[before.rfind("<p>").unwrap_or(0)..];
+            offset = end + PRE_END.len();
+
+            let block = CodeBlock {
+                part,
+                text: decode_entities(&strip_tags(&article[text_start..end])),
+                introduced_as_example: paragraph.to_lowercase().contains("example"),
+            };
+
+            blocks.push((block, &article[offset..]));
+        }
+    }
+
+    blocks
+}
+
+/// Every `<pre><code>` block in the statement, in order, as plain text.
+pub fn code_blocks(html: &str) -> Vec<CodeBlock> {
+    blocks_with_rest(html)
+        .into_iter()
+        .map(|(block, _)| block)
+        .collect()
+}
+
+/// The index of the block most likely to be the example input: the first one introduced as an
+/// example, or else the first one.
+pub fn likely_example(blocks: &[CodeBlock]) -> Option<usize> {
+    blocks
+        .iter()
+        .position(|block| block.introduced_as_example)
+        .or((!blocks.is_empty()).then_some(0))
+}
+
+/// A guess at part 1's answer for the code block at `index`: the walkthrough after an example
+/// gives its answer as highlighted code, so this is the first `<code><em>` after the block and
+/// before the next one. `None` if there is none, or the block is not in part 1.
+pub fn example_answer(html: &str, index: usize) -> Option<String> {
+    const START: &str = "<code><em>";
+    const END: &str = "</em></code>";
+
+    let blocks = blocks_with_rest(html);
+    let (block, rest) = blocks.get(index)?;
+
+    if block.part != 1 {
+        return None;
+    }
+
+    let walkthrough = &rest[..rest.find(PRE_START).unwrap_or(rest.len())];
+    let start = walkthrough.find(START)? + START.len();
+    let len = walkthrough[start..].find(END)?;
+
+    Some(decode_entities(&strip_tags(
+        &walkthrough[start..start + len],
+    )))
+}
+
+/// Whether a saved `puzzle.md` already has the second part, so the page need not be fetched again.
+pub fn is_complete(markdown: &str) -> bool {
+    markdown.contains(PART_TWO_HEADING)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!("{name}=\""))? + name.len() + 2;
+    let len = tag[start..].find('"')?;
+
+    Some(&tag[start..start + len])
+}
+
+/// Markdown for the handful of elements the site uses in a puzzle statement. Anything else (e.g.
+/// the `<span title>` easter eggs) is reduced to its text.
+fn to_markdown(article: &str, base_url: &str) -> String {
+    // Markdown cannot emphasise inside a code span, so emphasise the whole span instead.
+    let article = article
+        .replace("<code><em>", "<em><code>")
+        .replace("</em></code>", "</code></em>");
+
+    let mut md = String::new();
+    let mut in_pre = false;
+    let mut in_code = false;
+    let mut links = vec![];
+    let mut rest = article.as_str();
+
+    while !rest.is_empty() {
+        let Some(tag_start) = rest.strip_prefix('<') else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = decode_entities(&rest[..end]);
+            rest = &rest[end..];
+
+            if in_pre {
+                md += &text;
+                continue;
+            }
+
+            let text = text.replace('\n', " ");
+            let text = if md.is_empty() || md.ends_with('\n') {
+                text.trim_start()
+            } else {
+                &text
+            };
+
+            if in_code {
+                md += text;
+            } else {
+                md += &text.replace('\\', "\\\\").replace('*', "\\*");
+            }
+
+            continue;
+        };
+
+        let Some(end) = tag_start.find('>') else {
+            break;
+        };
+
+        let tag = &tag_start[..end];
+        rest = &tag_start[end + 1..];
+
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+
+        match (tag.split_whitespace().next().unwrap_or_default(), closing) {
+            ("h2", false) => md += "## ",
+            ("h2" | "p", true) => md += "\n\n",
+            ("pre", false) => {
+                in_pre = true;
+                md += "```\n";
+            }
+            ("pre", true) => {
+                in_pre = false;
+
+                if !md.ends_with('\n') {
+                    md.push('\n');
+                }
+
+                md += "```\n\n";
+            }
+            ("code", _) if !in_pre => {
+                in_code = !closing;
+                md.push('`');
+            }
+            ("em", _) if !in_pre => md.push('*'),
+            ("li", false) => md += "- ",
+            ("li" | "ul", true) => md.push('\n'),
+            ("a", false) => {
+                let href = attribute(tag, "href").unwrap_or_default();
+                let href = if href.starts_with('/') {
+                    format!("{base_url}{href}")
+                } else {
+                    href.to_string()
+                };
+
+                links.push(decode_entities(&href));
+                md.push('[');
+            }
+            ("a", true) => md += &format!("]({})", links.pop().unwrap_or_default()),
+            _ => {}
+        }
+    }
+
+    format!("{}\n", md.trim_end())
+}
@@ -0,0 +1,139 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::client::ClientError;
+use crate::date;
+
+pub const REQUEST_LOG_ENV: &str = "AOC_REQUEST_LOG";
+
+/// `$AOC_REQUEST_LOG`, or `.aoc-requests.log` in the repository (which git ignores).
+pub fn default_log_path() -> PathBuf {
+    env::var_os(REQUEST_LOG_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join(".aoc-requests.log"))
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_millis() as u64)
+}
+
+/// Keeps requests to the site within its automation guidelines. Every request is appended to a
+/// log, one line each:
+///
+/// ```text
+/// 1543640400000 2018-12-01 05:00:00 UTC GET https://adventofcode.com/2018/day/1/input 200
+/// ```
+///
+/// and the log is what the limits are checked against, so they hold across invocations.
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    /// How long to wait after the last request before sending another.
+    pub min_interval: Duration,
+    /// At most `burst_limit` requests are sent in any `burst_window`; past that, requests are
+    /// refused rather than delayed.
+    pub burst_limit: usize,
+    pub burst_window: Duration,
+    pub log_path: PathBuf,
+}
+
+impl Default for RateLimit {
+    /// One request every 3 seconds and 30 every 10 minutes, logged to [`default_log_path`].
+    fn default() -> Self {
+        RateLimit::new(default_log_path())
+    }
+}
+
+impl RateLimit {
+    pub fn new(log_path: impl Into<PathBuf>) -> RateLimit {
+        RateLimit {
+            min_interval: Duration::from_secs(3),
+            burst_limit: 30,
+            burst_window: Duration::from_secs(10 * 60),
+            log_path: log_path.into(),
+        }
+    }
+
+    fn log_error(&self, source: io::Error) -> ClientError {
+        ClientError::Cache {
+            path: self.log_path.clone(),
+            source,
+        }
+    }
+
+    /// When the logged requests were sent, oldest first.
+    fn sent_times(&self) -> Result<Vec<SystemTime>, ClientError> {
+        let log = match fs::read_to_string(&self.log_path) {
+            Ok(log) => log,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(source) => return Err(self.log_error(source)),
+        };
+
+        Ok(log
+            .lines()
+            .filter_map(|line| line.split(' ').next()?.parse().ok())
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis))
+            .collect())
+    }
+
+    /// Blocks until another request may be sent, or refuses with
+    /// [`ClientError::RateLimited`] if it would make a burst.
+    pub fn wait(&self) -> Result<(), ClientError> {
+        let sent = self.sent_times()?;
+        let now = SystemTime::now();
+
+        let in_window: Vec<_> = sent
+            .iter()
+            .filter(|time| now.duration_since(**time).unwrap_or_default() < self.burst_window)
+            .collect();
+
+        if in_window.len() >= self.burst_limit {
+            // Once this one is out of the window, there is room for one more.
+            let oldest = in_window
+                .get(in_window.len() - self.burst_limit)
+                .map_or(now, |time| **time);
+
+            return Err(ClientError::RateLimited {
+                retry_at: oldest + self.burst_window,
+            });
+        }
+
+        if let Some(last) = sent.last() {
+            let since = now.duration_since(*last).unwrap_or_default();
+            thread::sleep(self.min_interval.saturating_sub(since));
+        }
+
+        Ok(())
+    }
+
+    /// Appends a request sent at `sent_at` to the log, with the status it got or why it failed.
+    pub fn record(
+        &self,
+        sent_at: SystemTime,
+        method: &str,
+        url: &str,
+        outcome: &str,
+    ) -> Result<(), ClientError> {
+        if let Some(dir) = self.log_path.parent() {
+            fs::create_dir_all(dir).map_err(|source| self.log_error(source))?;
+        }
+
+        let mut log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .map_err(|source| self.log_error(source))?;
+
+        writeln!(
+            log,
+            "{} {} {method} {url} {outcome}",
+            unix_millis(sent_at),
+            date::format_time(sent_at)
+        )
+        .map_err(|source| self.log_error(source))
+    }
+}
@@ -0,0 +1,187 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::toml_file::{self, key, StateError};
+use crate::{Timings, DEFAULT_YEAR};
+
+#[derive(Debug, Copy, Clone)]
+pub struct Budget {
+    pub per_day: Duration,
+    pub total: Duration,
+    /// How much slower than its baseline a day may get, as a fraction, before it is flagged.
+    pub tolerance: f64,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget {
+            per_day: Duration::from_secs(1),
+            total: Duration::from_secs(1),
+            tolerance: 0.1,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Deserialize, Serialize)]
+struct BaselineEntry {
+    input_ns: u64,
+    part1_ns: u64,
+    part2_ns: u64,
+}
+
+impl BaselineEntry {
+    fn total(&self) -> Duration {
+        Duration::from_nanos(self.input_ns + self.part1_ns + self.part2_ns)
+    }
+}
+
+/// Wall times from a previous run, keyed by day. Timings only mean something on the machine that
+/// recorded them, so this lives under `target/` rather than in the repository.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Baseline {
+    #[serde(flatten)]
+    days: BTreeMap<String, BaselineEntry>,
+}
+
+pub fn default_baseline_path(year: u16) -> PathBuf {
+    let file_name = if year == DEFAULT_YEAR {
+        "aoc-baseline.toml".to_string()
+    } else {
+        format!("aoc-baseline-{year}.toml")
+    };
+
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join(file_name)
+}
+
+impl Baseline {
+    /// Reads the baseline at `path`; a missing file is an empty baseline.
+    pub fn load(path: &Path) -> Result<Baseline, StateError> {
+        toml_file::load(path)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StateError> {
+        toml_file::save(self, path)
+    }
+
+    pub fn get(&self, day: u8) -> Option<Duration> {
+        self.days.get(&key(day)).map(BaselineEntry::total)
+    }
+
+    pub fn set(&mut self, day: u8, timings: &Timings) {
+        self.days.insert(
+            key(day),
+            BaselineEntry {
+                input_ns: timings.input.as_nanos() as u64,
+                part1_ns: timings.part1.as_nanos() as u64,
+                part2_ns: timings.part2.as_nanos() as u64,
+            },
+        );
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportRow {
+    pub day: u8,
+    pub elapsed: Duration,
+    /// Percentage of the total wall time of all days in the report.
+    pub share: f64,
+    pub over_budget: bool,
+    pub baseline: Option<Duration>,
+    pub regressed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// Slowest day first.
+    pub rows: Vec<ReportRow>,
+    pub total: Duration,
+    pub over_total_budget: bool,
+}
+
+impl Report {
+    pub fn new(timings: &[(u8, Timings)], budget: &Budget, baseline: &Baseline) -> Report {
+        let total: Duration = timings.iter().map(|(_, timings)| timings.total()).sum();
+
+        let mut rows: Vec<_> = timings
+            .iter()
+            .map(|(day, timings)| {
+                let elapsed = timings.total();
+                let baseline = baseline.get(*day);
+
+                ReportRow {
+                    day: *day,
+                    elapsed,
+                    share: if total.is_zero() {
+                        0.0
+                    } else {
+                        100.0 * elapsed.as_secs_f64() / total.as_secs_f64()
+                    },
+                    over_budget: elapsed > budget.per_day,
+                    baseline,
+                    regressed: baseline.is_some_and(|baseline| {
+                        elapsed.as_secs_f64() > baseline.as_secs_f64() * (1.0 + budget.tolerance)
+                    }),
+                }
+            })
+            .collect();
+
+        rows.sort_by_key(|row| Reverse(row.elapsed));
+
+        Report {
+            rows,
+            total,
+            over_total_budget: total > budget.total,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timings(ms: u64) -> Timings {
+        Timings {
+            input: Duration::ZERO,
+            part1: Duration::from_millis(ms / 2),
+            part2: Duration::from_millis(ms - ms / 2),
+        }
+    }
+
+    #[test]
+    fn ranks_and_flags_days() {
+        let mut baseline = Baseline::default();
+        baseline.set(3, &timings(100));
+        baseline.set(14, &timings(1_000));
+
+        let budget = Budget {
+            per_day: Duration::from_millis(500),
+            total: Duration::from_secs(1),
+            tolerance: 0.1,
+        };
+
+        let report = Report::new(
+            &[(3, timings(200)), (9, timings(100)), (14, timings(1_050))],
+            &budget,
+            &baseline,
+        );
+
+        let days: Vec<_> = report.rows.iter().map(|row| row.day).collect();
+        assert_eq!(days, [14, 3, 9]);
+
+        assert!(report.rows[0].over_budget);
+        assert!(!report.rows[0].regressed);
+        assert!(!report.rows[1].over_budget);
+        assert!(report.rows[1].regressed);
+        assert_eq!(report.rows[2].baseline, None);
+        assert!(!report.rows[2].regressed);
+
+        assert!(report.over_total_budget);
+        assert!((report.rows[2].share - 100.0 * 100.0 / 1350.0).abs() < 1e-9);
+    }
+}
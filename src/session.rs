@@ -0,0 +1,254 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::{ClientError, SESSION_ENV};
+use crate::date;
+use crate::toml_file::{self, StateError};
+
+pub const SESSIONS_FILE_ENV: &str = "AOC_SESSIONS_FILE";
+
+/// How long the site's session cookies tend to last. The site does not say, so this is only good
+/// enough for a reminder.
+pub const SESSION_LIFETIME: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// `$AOC_SESSIONS_FILE`, or `.aoc-sessions.toml` in the repository (which git ignores).
+pub fn default_path() -> PathBuf {
+    env::var_os(SESSIONS_FILE_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join(".aoc-sessions.toml"))
+}
+
+/// Where the inputs of a session other than the first one go, unless it is given a directory.
+/// `inputs/` is ignored by git, so other accounts' inputs are not committed by accident.
+pub fn default_input_dir(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("inputs")
+        .join(name)
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct StoredSession {
+    pub token: String,
+    /// Where this account's inputs live, instead of next to the solutions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_dir: Option<PathBuf>,
+    /// When the token was stored, in seconds since the Unix epoch.
+    pub added_at: u64,
+    /// The user the site reported the last time the token was checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked_at: Option<u64>,
+}
+
+impl StoredSession {
+    /// When the token probably stops working, counting from when it was stored.
+    pub fn expiry_guess(&self) -> SystemTime {
+        date::from_unix_seconds(self.added_at) + SESSION_LIFETIME
+    }
+}
+
+/// The session a command runs as.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    /// `None` for a token from `$SESSION`.
+    pub name: Option<String>,
+    pub token: String,
+    pub input_dir: Option<PathBuf>,
+}
+
+/// Named session tokens, e.g. a personal and a team account:
+///
+/// ```toml
+/// default = "personal"
+///
+/// [sessions.personal]
+/// token = "53616c74..."
+/// added_at = 1700000000
+///
+/// [sessions.team]
+/// token = "8f14e45f..."
+/// input_dir = "inputs/team"
+/// added_at = 1700000000
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct SessionStore {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub sessions: BTreeMap<String, StoredSession>,
+}
+
+impl SessionStore {
+    /// Reads the store at `path`; a missing file is an empty store.
+    pub fn load(path: &Path) -> Result<SessionStore, StateError> {
+        toml_file::load(path)
+    }
+
+    /// Writes the store, readable only by its owner where the platform allows, since the tokens
+    /// log in as the user.
+    pub fn save(&self, path: &Path) -> Result<(), StateError> {
+        toml_file::save(self, path)?;
+
+        #[cfg(unix)]
+        {
+            use std::fs;
+            use std::os::unix::fs::PermissionsExt;
+
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+                .map_err(StateError::io(path))?;
+        }
+
+        Ok(())
+    }
+
+    /// Stores `token` as `name`, replacing any token of that name. The first session becomes the
+    /// default and keeps its inputs next to the solutions; later ones get a directory of their own
+    /// unless `input_dir` says otherwise.
+    pub fn add(&mut self, name: &str, token: &str, input_dir: Option<PathBuf>) {
+        let input_dir = match (input_dir, self.sessions.get(name)) {
+            (Some(input_dir), _) => Some(input_dir),
+            (None, Some(existing)) => existing.input_dir.clone(),
+            (None, None) if self.sessions.is_empty() => None,
+            (None, None) => Some(default_input_dir(name)),
+        };
+
+        self.sessions.insert(
+            name.to_string(),
+            StoredSession {
+                token: token.to_string(),
+                input_dir,
+                added_at: date::unix_seconds(SystemTime::now()),
+                user: None,
+                checked_at: None,
+            },
+        );
+
+        if self.default.is_none() {
+            self.default = Some(name.to_string());
+        }
+    }
+
+    /// Forgets `name`. Returns `false` if there is no such session.
+    pub fn remove(&mut self, name: &str) -> bool {
+        if self.sessions.remove(name).is_none() {
+            return false;
+        }
+
+        if self.default.as_deref() == Some(name) {
+            self.default = self.sessions.keys().next().cloned();
+        }
+
+        true
+    }
+
+    /// Makes `name` the session used when none is given. Returns `false` if there is no such
+    /// session.
+    pub fn set_default(&mut self, name: &str) -> bool {
+        let exists = self.sessions.contains_key(name);
+
+        if exists {
+            self.default = Some(name.to_string());
+        }
+
+        exists
+    }
+
+    /// Remembers who the site said `name` logs in as.
+    pub fn record_check(&mut self, name: &str, user: &str) {
+        if let Some(session) = self.sessions.get_mut(name) {
+            session.user = Some(user.to_string());
+            session.checked_at = Some(date::unix_seconds(SystemTime::now()));
+        }
+    }
+
+    fn named(&self, name: &str) -> Result<Session, ClientError> {
+        let stored = self
+            .sessions
+            .get(name)
+            .ok_or_else(|| ClientError::UnknownSession(name.to_string()))?;
+
+        Ok(Session {
+            name: Some(name.to_string()),
+            token: stored.token.clone(),
+            input_dir: stored.input_dir.clone(),
+        })
+    }
+
+    /// The session called `name` if one is given, else the token in `$SESSION`, else the default
+    /// session.
+    pub fn select(&self, name: Option<&str>) -> Result<Session, ClientError> {
+        if let Some(name) = name {
+            return self.named(name);
+        }
+
+        if let Ok(token) = env::var(SESSION_ENV) {
+            return Ok(Session {
+                name: None,
+                token,
+                input_dir: None,
+            });
+        }
+
+        match &self.default {
+            Some(name) => self.named(name),
+            None => Err(ClientError::MissingSession),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn later_sessions_get_their_own_inputs() {
+        let mut store = SessionStore::default();
+        store.add("personal", "aaa", None);
+        store.add("team", "bbb", None);
+        store.add("other", "ccc", Some(PathBuf::from("elsewhere")));
+
+        assert_eq!(store.default.as_deref(), Some("personal"));
+        assert_eq!(store.sessions["personal"].input_dir, None);
+        assert_eq!(
+            store.sessions["team"].input_dir,
+            Some(default_input_dir("team"))
+        );
+        assert_eq!(
+            store.sessions["other"].input_dir,
+            Some(PathBuf::from("elsewhere"))
+        );
+
+        store.add("personal", "ddd", None);
+        assert_eq!(store.sessions["personal"].token, "ddd");
+        assert_eq!(store.sessions["personal"].input_dir, None);
+
+        let team = store.select(Some("team")).unwrap();
+        assert_eq!(team.token, "bbb");
+        assert_eq!(team.input_dir, Some(default_input_dir("team")));
+        assert!(matches!(
+            store.select(Some("nobody")),
+            Err(ClientError::UnknownSession(_))
+        ));
+    }
+
+    #[test]
+    fn removing_the_default_picks_another() {
+        let mut store = SessionStore::default();
+        store.add("personal", "aaa", None);
+        store.add("team", "bbb", None);
+
+        assert!(!store.set_default("nobody"));
+        assert!(store.set_default("team"));
+        assert!(store.remove("team"));
+        assert!(!store.remove("team"));
+        assert_eq!(store.default.as_deref(), Some("personal"));
+
+        assert!(store.remove("personal"));
+        assert_eq!(store.default, None);
+    }
+}
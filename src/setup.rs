@@ -0,0 +1,273 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::{day_dir, DEFAULT_YEAR};
+
+pub const DEFAULT_TEMPLATE: &str = "default";
+
+#[derive(Debug)]
+pub enum SetupError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// A template or registry file that setting up a day cannot work with.
+    Scaffold {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl Display for SetupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SetupError::Io { path, source } => {
+                write!(f, "Cannot access {}: {source}", path.display())
+            }
+            SetupError::Scaffold { path, message } => write!(f, "{}: {message}", path.display()),
+        }
+    }
+}
+
+impl Error for SetupError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SetupError::Io { source, .. } => Some(source),
+            SetupError::Scaffold { .. } => None,
+        }
+    }
+}
+
+/// Where the day binaries live.
+pub fn default_src_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/bin")
+}
+
+/// The library's registry of days, see [`register_day`].
+pub fn default_days_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/days.rs")
+}
+
+/// The tests of every day, see [`register_test`].
+pub fn default_tests_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/days.rs")
+}
+
+/// Where the templates for new days live, one `<name>.rs` each. They are not compiled as they are,
+/// since the placeholders are not valid Rust.
+pub fn default_template_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("templates")
+}
+
+/// The names of the templates in `template_dir`, sorted.
+pub fn templates(template_dir: &Path) -> Result<Vec<String>, SetupError> {
+    let mut names: Vec<_> = fs::read_dir(template_dir)
+        .map_err(file_io(template_dir))?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+
+            (path.extension()? == "rs").then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .collect();
+
+    names.sort();
+
+    Ok(names)
+}
+
+fn file_io(path: &Path) -> impl FnOnce(io::Error) -> SetupError + '_ {
+    |source| SetupError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+fn scaffold(path: &Path, message: impl Into<String>) -> SetupError {
+    SetupError::Scaffold {
+        path: path.to_path_buf(),
+        message: message.into(),
+    }
+}
+
+/// Fills in `{{day}}`, `{{day_padded}}` and `{{year}}`. Any other `{{...}}` is an error, so that a
+/// typo does not end up in the generated code.
+pub fn render(template: &str, path: &Path, year: u16, day: u8) -> Result<String, SetupError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        rendered += &rest[..start];
+        rest = &rest[start + 2..];
+
+        let end = rest
+            .find("}}")
+            .ok_or_else(|| scaffold(path, "unclosed {{"))?;
+
+        match rest[..end].trim() {
+            "day" => rendered += &day.to_string(),
+            "day_padded" => rendered += &format!("{day:02}"),
+            "year" => rendered += &year.to_string(),
+            name => {
+                return Err(scaffold(
+                    path,
+                    format!("unknown placeholder {{{{{name}}}}}"),
+                ))
+            }
+        }
+
+        rest = &rest[end + 2..];
+    }
+
+    rendered += rest;
+
+    Ok(rendered)
+}
+
+/// Creates `main.rs` in the day's directory (see [`day_dir`]) from the template at
+/// `template_path`. Fails if the day's directory already exists, so existing solutions are never
+/// overwritten.
+pub fn create_day(
+    src_dir: &Path,
+    template_path: &Path,
+    year: u16,
+    day: u8,
+) -> Result<PathBuf, SetupError> {
+    let day_dir = day_dir(src_dir, year, day);
+    let main_path = day_dir.join("main.rs");
+
+    let template = fs::read_to_string(template_path).map_err(file_io(template_path))?;
+    let rust_file = render(&template, template_path, year, day)?;
+
+    if let Some(year_dir) = day_dir.parent() {
+        fs::create_dir_all(year_dir).map_err(file_io(year_dir))?;
+    }
+
+    fs::create_dir(&day_dir).map_err(file_io(&day_dir))?;
+
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&main_path)
+        .and_then(|mut file| file.write_all(rust_file.as_bytes()))
+        .map_err(file_io(&main_path))?;
+
+    Ok(main_path)
+}
+
+/// The name of the day's module in `src/days.rs`, and of its type: `day05::Day05` for the
+/// repository's own year, `y2019_day05::Day05` for others.
+pub fn module_name(year: u16, day: u8) -> String {
+    if year == DEFAULT_YEAR {
+        format!("day{day:02}")
+    } else {
+        format!("y{year}_day{day:02}")
+    }
+}
+
+/// Adds the day to the library's registry at `days_path` (`src/days.rs`): a `#[path]` module after
+/// the last one, and an entry at the end of `DAYS`. Returns `false` if it is already there.
+pub fn register_day(days_path: &Path, year: u16, day: u8) -> Result<bool, SetupError> {
+    let name = module_name(year, day);
+    let source = fs::read_to_string(days_path).map_err(file_io(days_path))?;
+    let mut lines: Vec<String> = source.lines().map(String::from).collect();
+
+    if lines.iter().any(|line| *line == format!("pub mod {name};")) {
+        return Ok(false);
+    }
+
+    let days_start = lines
+        .iter()
+        .position(|line| line.starts_with("pub const DAYS"))
+        .ok_or_else(|| scaffold(days_path, "no DAYS registry"))?;
+    let days_end = lines[days_start..]
+        .iter()
+        .position(|line| line == "];")
+        .map(|i| days_start + i)
+        .ok_or_else(|| scaffold(days_path, "DAYS is not closed by a \"];\" line"))?;
+
+    lines.insert(days_end, format!("    day::<{name}::Day{day:02}>(),"));
+
+    let last_module = lines
+        .iter()
+        .rposition(|line| line.starts_with("pub mod "))
+        .ok_or_else(|| scaffold(days_path, "no day modules"))?;
+    let path = day_dir(Path::new("bin"), year, day).join("main.rs");
+
+    lines.insert(last_module + 1, format!("pub mod {name};"));
+    lines.insert(last_module + 1, format!("#[path = \"{}\"]", path.display()));
+
+    fs::write(days_path, lines.join("\n") + "\n").map_err(file_io(days_path))?;
+
+    Ok(true)
+}
+
+/// Why a newly registered example test is ignored: the new day has no `ex.txt` or answers yet, and
+/// the template's solution would not give them anyway.
+pub const EXAMPLE_TEST_IGNORED: &str = "fill in ex.toml, then remove this line";
+
+/// Adds a test that runs the day's example to the tests at `tests_path` (`tests/days.rs`), ignored
+/// (see [`EXAMPLE_TEST_IGNORED`]) so that the suite stays green until the day is solved. Returns
+/// `false` if it is already there.
+pub fn register_test(tests_path: &Path, year: u16, day: u8) -> Result<bool, SetupError> {
+    let name = module_name(year, day);
+    let test_name = format!("{name}_example");
+    let mut source = fs::read_to_string(tests_path).map_err(file_io(tests_path))?;
+
+    if source.contains(&format!("fn {test_name}()")) {
+        return Ok(false);
+    }
+
+    source += &format!(
+        "\n#[test]\n#[ignore = \"{EXAMPLE_TEST_IGNORED}\"]\nfn {test_name}() {{\n    \
+         check_example::<{name}::Day{day:02}>();\n}}\n"
+    );
+
+    fs::write(tests_path, source).map_err(file_io(tests_path))?;
+
+    Ok(true)
+}
+
+/// Writes `input` to `path`, creating its directory and replacing any previous contents.
+pub fn write_input(path: &Path, input: &str) -> Result<(), SetupError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(file_io(dir))?;
+    }
+
+    fs::write(path, input).map_err(file_io(path))
+}
+
+/// Writes `example` to `<day_dir>/ex.txt`, and a stub `ex.toml` with `part1` set to `answer` if
+/// there is no `ex.toml` yet. Expected answers already filled in by hand are kept.
+pub fn write_example(
+    day_dir: &Path,
+    example: &str,
+    answer: Option<&str>,
+) -> Result<(), SetupError> {
+    write_input(&day_dir.join("ex.txt"), example)?;
+
+    let expected_path = day_dir.join("ex.toml");
+
+    let stub = match answer {
+        Some(answer) => format!(
+            "# Guessed from the puzzle statement, check it before trusting the example.\n\
+             part1 = {}\n",
+            toml::Value::String(answer.to_string())
+        ),
+        None => "# The expected answers for ex.txt, e.g.\n# part1 = \"42\"\n".to_string(),
+    };
+
+    match OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&expected_path)
+    {
+        Ok(mut file) => file
+            .write_all(stub.as_bytes())
+            .map_err(file_io(&expected_path)),
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => Ok(()),
+        Err(err) => Err(file_io(&expected_path)(err)),
+    }
+}
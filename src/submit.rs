@@ -0,0 +1,229 @@
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+use std::time::Duration;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::toml_file::{self, StateError};
+
+/// What the site said about a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint about which way.
+    Wrong,
+    /// Submitted too soon after the previous answer; nothing was checked.
+    RateLimited {
+        wait: Option<Duration>,
+    },
+    /// The part was already solved (or part 1 is not solved yet); nothing was checked.
+    AlreadySolved,
+}
+
+impl Outcome {
+    /// Parses the `<article>` of the page returned after submitting. `None` if the page says
+    /// something we do not recognise.
+    pub fn from_html(html: &str) -> Option<Outcome> {
+        if html.contains("That's the right answer") {
+            Some(Outcome::Correct)
+        } else if html.contains("That's not the right answer") {
+            Some(if html.contains("your answer is too high") {
+                Outcome::TooHigh
+            } else if html.contains("your answer is too low") {
+                Outcome::TooLow
+            } else {
+                Outcome::Wrong
+            })
+        } else if html.contains("You gave an answer too recently") {
+            let re = Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").unwrap();
+            let wait = re.captures(html).map(|cap| {
+                let minutes: u64 = cap.get(1).map_or(0, |m| m.as_str().parse().unwrap_or(0));
+                let seconds: u64 = cap[2].parse().unwrap_or(0);
+
+                Duration::from_secs(minutes * 60 + seconds)
+            });
+
+            Some(Outcome::RateLimited { wait })
+        } else if html.contains("You don't seem to be solving the right level") {
+            Some(Outcome::AlreadySolved)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong)
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::TooHigh => write!(f, "wrong, too high"),
+            Outcome::TooLow => write!(f, "wrong, too low"),
+            Outcome::Wrong => write!(f, "wrong"),
+            Outcome::RateLimited { wait: Some(wait) } => {
+                write!(f, "rate limited, wait {}s", wait.as_secs())
+            }
+            Outcome::RateLimited { wait: None } => write!(f, "rate limited"),
+            Outcome::AlreadySolved => write!(f, "already solved, or not the current part"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct PartHistory {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wrong: Vec<String>,
+    /// The lowest answer the site said is too high.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub too_high: Option<i64>,
+    /// The highest answer the site said is too low.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub too_low: Option<i64>,
+}
+
+/// Wrong answers already submitted for one day, so they are not submitted again.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct History {
+    #[serde(default)]
+    pub part1: PartHistory,
+    #[serde(default)]
+    pub part2: PartHistory,
+}
+
+impl History {
+    /// Reads the history at `path`; a missing file is an empty history.
+    pub fn load(path: &Path) -> Result<History, StateError> {
+        toml_file::load(path)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StateError> {
+        toml_file::save(self, path)
+    }
+
+    fn part(&self, part: u8) -> &PartHistory {
+        if part == 1 {
+            &self.part1
+        } else {
+            &self.part2
+        }
+    }
+
+    fn part_mut(&mut self, part: u8) -> &mut PartHistory {
+        if part == 1 {
+            &mut self.part1
+        } else {
+            &mut self.part2
+        }
+    }
+
+    /// Why `answer` is already known to be wrong, if it is.
+    pub fn known_wrong(&self, part: u8, answer: &str) -> Option<String> {
+        let history = self.part(part);
+
+        if history.wrong.iter().any(|wrong| wrong == answer) {
+            return Some(format!("{answer} was already submitted and is wrong"));
+        }
+
+        let number: i64 = answer.parse().ok()?;
+
+        match (history.too_high, history.too_low) {
+            (Some(too_high), _) if number >= too_high => {
+                Some(format!("{too_high} was too high, so {answer} is too"))
+            }
+            (_, Some(too_low)) if number <= too_low => {
+                Some(format!("{too_low} was too low, so {answer} is too"))
+            }
+            _ => None,
+        }
+    }
+
+    /// Remembers a wrong `answer`; other outcomes leave the history untouched.
+    pub fn record(&mut self, part: u8, answer: &str, outcome: &Outcome) {
+        if !outcome.is_wrong() {
+            return;
+        }
+
+        let history = self.part_mut(part);
+        history.wrong.push(answer.to_string());
+
+        let Ok(number) = answer.parse::<i64>() else {
+            return;
+        };
+
+        match outcome {
+            Outcome::TooHigh => {
+                history.too_high = Some(history.too_high.map_or(number, |high| high.min(number)))
+            }
+            Outcome::TooLow => {
+                history.too_low = Some(history.too_low.map_or(number, |low| low.max(number)))
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_site_responses() {
+        let page = |text: &str| format!("<main>\n<article><p>{text}</p></article>\n</main>");
+
+        for (text, outcome) in [
+            (
+                "That's the right answer! You are one gold star closer to fixing the time stream.",
+                Outcome::Correct,
+            ),
+            (
+                "That's not the right answer; your answer is too high.  If you're stuck, ...",
+                Outcome::TooHigh,
+            ),
+            (
+                "That's not the right answer; your answer is too low.",
+                Outcome::TooLow,
+            ),
+            (
+                "That's not the right answer.  If you're stuck, make sure you're using the full input data.",
+                Outcome::Wrong,
+            ),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait.",
+                Outcome::RateLimited { wait: Some(Duration::from_secs(65)) },
+            ),
+            (
+                "You gave an answer too recently.  You have 37s left to wait.",
+                Outcome::RateLimited { wait: Some(Duration::from_secs(37)) },
+            ),
+            (
+                "You don't seem to be solving the right level.  Did you already complete it?",
+                Outcome::AlreadySolved,
+            ),
+        ] {
+            assert_eq!(Outcome::from_html(&page(text)), Some(outcome), "{text}");
+        }
+
+        assert_eq!(Outcome::from_html(&page("Something else")), None);
+    }
+
+    #[test]
+    fn refuses_known_wrong_answers() {
+        let mut history = History::default();
+        history.record(1, "500", &Outcome::TooHigh);
+        history.record(1, "100", &Outcome::TooLow);
+        history.record(1, "250", &Outcome::Wrong);
+        history.record(1, "300", &Outcome::RateLimited { wait: None });
+
+        assert!(history.known_wrong(1, "250").is_some());
+        assert!(history.known_wrong(1, "600").is_some());
+        assert!(history.known_wrong(1, "50").is_some());
+        assert!(history.known_wrong(1, "300").is_none());
+        assert!(history.known_wrong(2, "250").is_none());
+    }
+}
@@ -0,0 +1,81 @@
+//! The TOML files state is kept in between runs: known answers, timing baselines, submission
+//! histories, sessions and the expected answers of examples.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+#[derive(Debug)]
+pub enum StateError {
+    Io { path: PathBuf, source: io::Error },
+    InvalidToml { path: PathBuf, message: String },
+}
+
+impl StateError {
+    pub fn io(path: &Path) -> impl FnOnce(io::Error) -> StateError + '_ {
+        |source| StateError::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+}
+
+impl Display for StateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::Io { path, source } => {
+                write!(f, "Cannot access {}: {source}", path.display())
+            }
+            StateError::InvalidToml { path, message } => {
+                write!(f, "Invalid TOML in {}: {message}", path.display())
+            }
+        }
+    }
+}
+
+impl Error for StateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StateError::Io { source, .. } => Some(source),
+            StateError::InvalidToml { .. } => None,
+        }
+    }
+}
+
+/// The table a day's entries go under in files that hold every day, e.g. `day05`.
+pub fn key(day: u8) -> String {
+    format!("day{day:02}")
+}
+
+/// Reads the file at `path`; a missing file is the default, e.g. an empty registry.
+pub fn load<T: DeserializeOwned + Default>(path: &Path) -> Result<T, StateError> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(source) => return Err(StateError::io(path)(source)),
+    };
+
+    toml::from_str(&raw).map_err(|err| StateError::InvalidToml {
+        path: path.to_path_buf(),
+        message: err.message().to_string(),
+    })
+}
+
+/// Writes `value` to `path`, creating its directory and replacing any previous contents.
+pub fn save<T: Serialize>(value: &T, path: &Path) -> Result<(), StateError> {
+    let raw = toml::to_string(value).map_err(|err| StateError::InvalidToml {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(StateError::io(dir))?;
+    }
+
+    fs::write(path, raw).map_err(StateError::io(path))
+}
@@ -0,0 +1,38 @@
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
+
+type Int = u16;
+type InputType = Vec<Int>;
+
+pub struct Day{{day_padded}};
+
+impl Solution for Day{{day_padded}} {
+    const YEAR: u16 = {{year}};
+    const DAY: u8 = {{day}};
+
+    type Input = InputType;
+    type Output1 = Int;
+    type Output2 = Int;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<InputType> {
+        Ok(input
+            .trim()
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                parser.line(line.parse().map_err(|err| Error::parse(i, line, err)))
+            })
+            .collect())
+    }
+
+    fn part1(_input: InputType) -> Result<Int> {
+        Err(Error::Unsolved)
+    }
+
+    fn part2(_input: InputType) -> Result<Int> {
+        Err(Error::Unsolved)
+    }
+}
+
+pub fn main() {
+    run::<Day{{day_padded}}>();
+}
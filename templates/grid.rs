@@ -0,0 +1,83 @@
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
+
+type Int = usize;
+
+/// The input's characters, row by row.
+#[derive(Debug, Clone)]
+pub struct Grid {
+    cells: Vec<Vec<char>>,
+    width: usize,
+}
+
+impl Grid {
+    fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn get(&self, x: usize, y: usize) -> Option<char> {
+        self.cells.get(y)?.get(x).copied()
+    }
+
+    /// The in-bounds cells next to `(x, y)`, diagonals included.
+    fn neighbours(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&delta| delta != (0, 0))
+            .filter_map(move |(dx, dy)| {
+                let x = x.checked_add_signed(dx)?;
+                let y = y.checked_add_signed(dy)?;
+
+                (x < self.width && y < self.height()).then_some((x, y))
+            })
+    }
+}
+
+type InputType = Grid;
+
+pub struct Day{{day_padded}};
+
+impl Solution for Day{{day_padded}} {
+    const YEAR: u16 = {{year}};
+    const DAY: u8 = {{day}};
+
+    type Input = InputType;
+    type Output1 = Int;
+    type Output2 = Int;
+
+    fn parse(input: &str, _parser: &mut Parser) -> Result<InputType> {
+        let cells: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+        let width = cells.first().map_or(0, Vec::len);
+
+        if let Some((i, line)) = input
+            .lines()
+            .enumerate()
+            .find(|(_, line)| line.chars().count() != width)
+        {
+            return Err(Error::parse(i, line, format!("expected {width} columns")));
+        }
+
+        Ok(Grid { cells, width })
+    }
+
+    fn part1(input: InputType) -> Result<Int> {
+        let mut count = 0;
+
+        for y in 0..input.height() {
+            for x in 0..input.width {
+                if input.get(x, y) == Some('#') {
+                    count += input.neighbours(x, y).count();
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    fn part2(_input: InputType) -> Result<Int> {
+        Err(Error::Unsolved)
+    }
+}
+
+pub fn main() {
+    run::<Day{{day_padded}}>();
+}
@@ -0,0 +1,57 @@
+use regex::Regex;
+
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
+
+type Int = i64;
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    name: String,
+    value: Int,
+}
+
+type InputType = Vec<Entry>;
+
+pub struct Day{{day_padded}};
+
+impl Solution for Day{{day_padded}} {
+    const YEAR: u16 = {{year}};
+    const DAY: u8 = {{day}};
+
+    type Input = InputType;
+    type Output1 = Int;
+    type Output2 = Int;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<InputType> {
+        let re = Regex::new(r"^(\w+): (-?\d+)$").unwrap();
+
+        Ok(input
+            .trim()
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                let cap = re
+                    .captures(line)
+                    .ok_or_else(|| Error::parse(i, line, "expected \"name: value\""))?;
+
+                Ok(Entry {
+                    name: cap[1].to_string(),
+                    value: cap[2].parse().map_err(|err| Error::parse(i, line, err))?,
+                })
+            })
+            .filter_map(|entry| parser.line(entry))
+            .collect())
+    }
+
+    fn part1(input: InputType) -> Result<Int> {
+        Ok(input.iter().map(|entry| entry.value).sum())
+    }
+
+    fn part2(input: InputType) -> Result<Int> {
+        Ok(input.iter().filter(|entry| !entry.name.is_empty()).count() as Int)
+    }
+}
+
+pub fn main() {
+    run::<Day{{day_padded}}>();
+}
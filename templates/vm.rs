@@ -0,0 +1,34 @@
+use advent_of_code_2018::elfcode::{Int, Machine, Program};
+use advent_of_code_2018::{run, Error, Parser, Result, Solution};
+
+type InputType = Program;
+
+pub struct Day{{day_padded}};
+
+impl Solution for Day{{day_padded}} {
+    const YEAR: u16 = {{year}};
+    const DAY: u8 = {{day}};
+
+    type Input = InputType;
+    type Output1 = Int;
+    type Output2 = Int;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<InputType> {
+        Program::parse(input, parser)
+    }
+
+    fn part1(input: InputType) -> Result<Int> {
+        let mut machine = Machine::<6>::new(input);
+        machine.run()?;
+
+        Ok(machine.registers[0])
+    }
+
+    fn part2(_input: InputType) -> Result<Int> {
+        Err(Error::Unsolved)
+    }
+}
+
+pub fn main() {
+    run::<Day{{day_padded}}>();
+}
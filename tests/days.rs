@@ -0,0 +1,368 @@
+use advent_of_code_2018::days::*;
+use advent_of_code_2018::example::check;
+use advent_of_code_2018::{parse, Error, InputSource, ParseMode, Solution};
+
+fn input<S: Solution>() -> S::Input {
+    let raw = InputSource::default().load(S::YEAR, S::DAY).unwrap();
+
+    parse::<S>(&raw, ParseMode::Strict).unwrap().0
+}
+
+/// Runs every part of the day's `ex.txt` that has an expected answer in `ex.toml`.
+fn check_example<S: Solution>() {
+    let source = InputSource::Day {
+        dir: None,
+        example: true,
+    };
+
+    let raw = source.load(S::YEAR, S::DAY).unwrap();
+    let expected = source.expected(S::YEAR, S::DAY).unwrap().unwrap();
+    let report = check::<S>(&raw, &expected).unwrap();
+
+    assert!(
+        report.part1.is_some() || report.part2.is_some(),
+        "ex.toml has no expected answers"
+    );
+
+    for check in [report.part1, report.part2].into_iter().flatten() {
+        assert_eq!(check.answer, Ok(check.expected));
+    }
+}
+
+#[test]
+fn day01_part1() {
+    assert_eq!(day01::Day01::part1(input::<day01::Day01>()).unwrap(), 516);
+}
+
+#[test]
+fn day01_part2() {
+    assert_eq!(day01::Day01::part2(input::<day01::Day01>()).unwrap(), 71892);
+}
+
+#[test]
+fn day02_part1() {
+    assert_eq!(day02::Day02::part1(input::<day02::Day02>()).unwrap(), 7872);
+}
+
+#[test]
+fn day02_part2() {
+    assert_eq!(
+        day02::Day02::part2(input::<day02::Day02>()).unwrap(),
+        "tjxmoewpdkyaihvrndfluwbzc"
+    );
+}
+
+#[test]
+fn day03_part1() {
+    assert_eq!(
+        day03::Day03::part1(input::<day03::Day03>()).unwrap(),
+        101565
+    );
+}
+
+#[test]
+fn day03_part2() {
+    assert_eq!(day03::Day03::part2(input::<day03::Day03>()).unwrap(), 656);
+}
+
+#[test]
+fn day04_part1() {
+    assert_eq!(day04::Day04::part1(input::<day04::Day04>()).unwrap(), 14346);
+}
+
+#[test]
+fn day04_part2() {
+    assert_eq!(day04::Day04::part2(input::<day04::Day04>()).unwrap(), 5705);
+}
+
+#[test]
+fn day05_part1() {
+    assert_eq!(day05::Day05::part1(input::<day05::Day05>()).unwrap(), 9704);
+}
+
+#[test]
+fn day05_part2() {
+    assert_eq!(day05::Day05::part2(input::<day05::Day05>()).unwrap(), 6942);
+}
+
+#[test]
+fn day06_part1() {
+    assert_eq!(day06::Day06::part1(input::<day06::Day06>()).unwrap(), 4166);
+}
+
+#[test]
+fn day06_part2() {
+    assert_eq!(day06::Day06::part2(input::<day06::Day06>()).unwrap(), 42250);
+}
+
+#[test]
+fn day06_example() {
+    check_example::<day06::Day06>();
+}
+
+#[test]
+fn day06_part2_covers_the_last_row_and_column() {
+    let (input, _) = parse::<day06::Day06>("5, 5", ParseMode::Strict).unwrap();
+
+    assert_eq!(day06::Day06::part2(input).unwrap(), 36);
+}
+
+#[test]
+fn day07_part1() {
+    assert_eq!(
+        day07::Day07::part1(input::<day07::Day07>()).unwrap(),
+        "JDEKPFABTUHOQSXVYMLZCNIGRW"
+    );
+}
+
+#[test]
+fn day07_part2() {
+    assert_eq!(day07::Day07::part2(input::<day07::Day07>()).unwrap(), 1048);
+}
+
+#[test]
+fn day07_example() {
+    check_example::<day07::Day07>();
+}
+
+#[test]
+fn day08_part1() {
+    assert_eq!(day08::Day08::part1(input::<day08::Day08>()).unwrap(), 36566);
+}
+
+#[test]
+fn day08_part2() {
+    assert_eq!(day08::Day08::part2(input::<day08::Day08>()).unwrap(), 30548);
+}
+
+#[test]
+fn day08_example() {
+    check_example::<day08::Day08>();
+}
+
+#[test]
+fn day09_part1() {
+    assert_eq!(
+        day09::Day09::part1(input::<day09::Day09>()).unwrap(),
+        371284
+    );
+}
+
+#[test]
+#[ignore = "slow"]
+fn day09_part2() {
+    assert_eq!(
+        day09::Day09::part2(input::<day09::Day09>()).unwrap(),
+        3038972494
+    );
+}
+
+#[test]
+fn day09_example() {
+    check_example::<day09::Day09>();
+}
+
+#[test]
+fn day10_part2() {
+    assert_eq!(day10::Day10::part2(input::<day10::Day10>()).unwrap(), 10101);
+}
+
+#[test]
+fn day10_points_that_never_meet_have_no_solution() {
+    let (input, _) = parse::<day10::Day10>(
+        "position=< 0,  0> velocity=< 1,  0>\n\
+         position=< 0, 20> velocity=<-1,  0>",
+        ParseMode::Strict,
+    )
+    .unwrap();
+
+    assert!(matches!(
+        day10::Day10::part1(input),
+        Err(Error::NoSolution(_))
+    ));
+}
+
+#[test]
+fn day11_part1() {
+    assert_eq!(
+        day11::Day11::part1(input::<day11::Day11>()).unwrap(),
+        "243,34"
+    );
+}
+
+#[test]
+#[ignore = "slow"]
+fn day11_part2() {
+    assert_eq!(
+        day11::Day11::part2(input::<day11::Day11>()).unwrap(),
+        "90,214,15"
+    );
+}
+
+#[test]
+fn day12_part1() {
+    assert_eq!(day12::Day12::part1(input::<day12::Day12>()).unwrap(), 3605);
+}
+
+#[test]
+fn day12_part2_is_not_solved_yet() {
+    assert_eq!(
+        day12::Day12::part2(input::<day12::Day12>()),
+        Err(Error::Unsolved)
+    );
+}
+
+#[test]
+fn day12_example() {
+    check_example::<day12::Day12>();
+}
+
+#[test]
+fn day13_part1() {
+    assert_eq!(
+        day13::Day13::part1(input::<day13::Day13>()).unwrap(),
+        "74,87"
+    );
+}
+
+#[test]
+fn day13_part2() {
+    assert_eq!(
+        day13::Day13::part2(input::<day13::Day13>()).unwrap(),
+        "29,74"
+    );
+}
+
+#[test]
+fn day13_example() {
+    check_example::<day13::Day13>();
+}
+
+fn day14_input(raw: &str) -> Vec<u8> {
+    parse::<day14::Day14>(raw, ParseMode::Strict).unwrap().0
+}
+
+/// Day 14's input is a single number, so the puzzle's examples are inlined rather than in `ex.txt`.
+#[test]
+fn day14_examples_part1() {
+    for (input, expected) in [
+        ("9", "5158916779"),
+        ("5", "0124515891"),
+        ("18", "9251071085"),
+        ("2018", "5941429882"),
+    ] {
+        assert_eq!(day14::Day14::part1(day14_input(input)).unwrap(), expected);
+    }
+}
+
+#[test]
+fn day14_examples_part2() {
+    for (input, expected) in [("51589", 9), ("01245", 5), ("92510", 18), ("59414", 2018)] {
+        assert_eq!(day14::Day14::part2(day14_input(input)).unwrap(), expected);
+    }
+}
+
+#[test]
+fn day14_part1_refuses_more_recipes_than_it_makes() {
+    for input in ["49999991", "18446744073709551615", "99999999999999999999"] {
+        assert!(matches!(
+            day14::Day14::part1(day14_input(input)),
+            Err(Error::OutOfRange(_))
+        ));
+    }
+}
+
+#[test]
+fn day14_digits_that_do_not_turn_up_have_no_solution() {
+    // 200 is not in the first 50 × 10³ recipes.
+    assert!(matches!(
+        day14::Day14::part2(day14_input("200")),
+        Err(Error::NoSolution(_))
+    ));
+}
+
+#[test]
+fn day16_part1() {
+    assert_eq!(day16::Day16::part1(input::<day16::Day16>()).unwrap(), 547);
+}
+
+#[test]
+fn day16_part2() {
+    assert_eq!(day16::Day16::part2(input::<day16::Day16>()).unwrap(), 582);
+}
+
+#[test]
+fn day16_example() {
+    check_example::<day16::Day16>();
+}
+
+#[test]
+fn day16_manual_without_a_program_has_no_part2() {
+    let raw = "Before: [3, 2, 1, 1]\n9 2 1 2\nAfter:  [3, 2, 2, 1]\n";
+    let (input, _) = parse::<day16::Day16>(raw, ParseMode::Strict).unwrap();
+
+    assert_eq!(day16::Day16::part1(input.clone()).unwrap(), 1);
+    assert!(matches!(day16::Day16::part2(input), Err(Error::Invalid(_))));
+}
+
+#[test]
+fn strict_parse_lists_every_rejected_line() {
+    let raw = "+1\nfoo\n-2\n+\n+3";
+
+    let Err(Error::Rejected(rejected)) = parse::<day01::Day01>(raw, ParseMode::Strict) else {
+        panic!("expected the input to be rejected");
+    };
+
+    let lines: Vec<_> = rejected
+        .iter()
+        .map(|err| match err {
+            Error::Parse { line, text, .. } => (*line, text.as_str()),
+            _ => panic!("expected a parse error, got {err}"),
+        })
+        .collect();
+
+    assert_eq!(lines, [(2, "foo"), (4, "+")]);
+}
+
+#[test]
+fn lenient_parse_skips_malformed_lines() {
+    let raw = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5 2x2\n#3 @ 5,5: 2x2";
+    let (input, skipped) = parse::<day03::Day03>(raw, ParseMode::Lenient).unwrap();
+
+    assert_eq!(skipped, 1);
+    assert_eq!(day03::Day03::part1(input).unwrap(), 4);
+}
+
+#[test]
+fn day07_cycle_has_no_solution() {
+    let (input, _) = parse::<day07::Day07>(
+        "Step A must be finished before step B can begin.\n\
+         Step B must be finished before step A can begin.",
+        ParseMode::Strict,
+    )
+    .unwrap();
+
+    assert!(matches!(
+        day07::Day07::part1(input.clone()),
+        Err(Error::NoSolution(_))
+    ));
+    assert!(matches!(
+        day07::Day07::part2(input),
+        Err(Error::NoSolution(_))
+    ));
+}
+
+#[test]
+fn day08_bad_number_is_an_error_in_lenient_mode() {
+    assert!(matches!(
+        parse::<day08::Day08>("2 3 0 3 10 11 x 12 1 1 0 1 99 2 1 1 2", ParseMode::Lenient),
+        Err(Error::Parse { line: 1, .. })
+    ));
+}
+
+#[test]
+fn day08_truncated_tree_is_invalid() {
+    let (input, _) = parse::<day08::Day08>("1 1 0 2 5", ParseMode::Strict).unwrap();
+
+    assert!(matches!(day08::Day08::part1(input), Err(Error::Invalid(_))));
+}
@@ -0,0 +1,421 @@
+use advent_of_code_2018::elfcode::disassemble::Flow;
+use advent_of_code_2018::elfcode::samples::{self, Manual};
+use advent_of_code_2018::elfcode::{Instruction, Machine, Mode, Opcode, Program, OPCODES};
+use advent_of_code_2018::{Error, ParseMode, Parser};
+
+/// Sums the divisors of a number it works out first, the slow way.
+const SOURCE: &str = include_str!("fixtures/elfcode.txt");
+
+/// Day 16's samples and test program.
+const MANUAL: &str = include_str!("../src/bin/day16/input.txt");
+
+fn program(source: &str) -> Program {
+    Program::parse(source, &mut Parser::new(ParseMode::Strict)).unwrap()
+}
+
+fn assemble(source: &str) -> Result<Program, Error> {
+    Program::assemble(source)
+}
+
+/// A xorshift generator, so the random programs are the same on every run.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+
+        (self.0 % n as u64) as usize
+    }
+
+    fn program(&mut self) -> Program {
+        let ip_register = match self.below(3) {
+            0 => None,
+            _ => Some(self.below(6)),
+        };
+
+        let instructions = (0..self.below(40))
+            .map(|_| {
+                let opcode = OPCODES[self.below(OPCODES.len())];
+                let [a, b] = [self.below(40), self.below(40)].map(|n| match n {
+                    // Mostly registers, sometimes a bigger value.
+                    0..=33 => n % 7,
+                    _ => n * 1000,
+                });
+
+                Instruction::new(opcode, [a, b, self.below(6)])
+            })
+            .collect();
+
+        Program {
+            ip_register,
+            instructions,
+        }
+    }
+
+    /// Source for a program with the pointer bound to r5, which jumps to random labels and
+    /// otherwise only computes into r0 to r4. Also gives the address of every `jmp` and of the
+    /// label it goes to.
+    fn source(&mut self) -> (String, Vec<(usize, usize)>) {
+        let len = 1 + self.below(30);
+
+        // Any instruction but the first may have a label, and so may the end of the program.
+        let labels: Vec<_> = (1..=len).filter(|_| self.below(3) == 0).collect();
+
+        let mut source = "#ip 5\n#reg acc 0\n".to_string();
+        let mut jumps = vec![];
+
+        for pc in 0..=len {
+            if labels.contains(&pc) {
+                // On a line of its own or in front of the instruction.
+                let separator = if self.below(2) == 0 { "\n" } else { " " };
+                source += &format!("label{pc}:{separator}");
+            }
+
+            if pc == len {
+                source += "\n";
+                break;
+            }
+
+            if !labels.is_empty() && self.below(4) == 0 {
+                let target = labels[self.below(labels.len())];
+                source += &format!("jmp label{target}\n");
+                jumps.push((pc, target));
+                continue;
+            }
+
+            let opcode = OPCODES[self.below(OPCODES.len())];
+            let register = |rng: &mut Rng| match rng.below(5) {
+                0 => "acc".to_string(),
+                n => format!("r{n}"),
+            };
+            let [a, b] = opcode.modes().map(|mode| match mode {
+                Mode::Register => register(self),
+                Mode::Immediate | Mode::Ignored => self.below(100).to_string(),
+            });
+
+            source += &format!("{opcode} {a} {b} {} ; {pc}\n", register(self));
+        }
+
+        (source, jumps)
+    }
+}
+
+#[test]
+fn resolves_jumps_through_the_instruction_pointer() {
+    let program = program(SOURCE);
+
+    assert_eq!(program.flow(0), Flow::Jump(17));
+    assert_eq!(program.flow(3), Flow::Next);
+    assert_eq!(program.flow(5), Flow::Skip(1));
+    assert_eq!(program.flow(6), Flow::Jump(8));
+    assert_eq!(program.flow(16), Flow::Jump(257));
+    assert_eq!(program.flow(25), Flow::Skip(0));
+    assert_eq!(
+        program.jump_targets().into_iter().collect::<Vec<_>>(),
+        [1, 2, 3, 7, 8, 12, 16, 17, 27]
+    );
+    assert!(program.leaders().contains(&26));
+
+    let listing = program.disassemble();
+    assert!(listing.starts_with("#ip 3\n   0  addi 3 16 3       goto L17\n\nL1:\n"));
+    assert!(listing.contains("\nL16:\n  16  mulr 3 3 3        goto halt\n"));
+    assert!(listing.contains("  19  mulr 3 5 5        r5 *= 19\n"));
+}
+
+#[test]
+fn decompiles_loops_and_conditions() {
+    let program = program(SOURCE);
+
+    assert_eq!(
+        program.decompile().to_string(),
+        "goto L17
+L1:
+r4 = 1
+do {
+    r2 = 1
+    do {
+        r1 = r4 * r2
+        if r1 == r5 {
+            r0 += r4
+        }
+    } while ++r2 <= r5
+} while ++r4 <= r5
+halt
+L17:
+r5 += 2
+r5 *= r5
+r5 *= 19
+r5 *= 11
+r1 += 6
+r1 *= 22
+r1 += 13
+r5 += r1
+goto 26 + r0
+goto L1
+L27:
+r1 = 27
+r1 *= 28
+r1 += 29
+r1 *= 30
+r1 *= 14
+r1 *= 32
+r5 += r1
+r0 = 0
+goto L1
+"
+    );
+}
+
+#[test]
+fn any_pointer_register_can_be_disassembled() {
+    for ip in [6, 1 << 40, usize::MAX] {
+        let program = program(&format!(
+            "#ip {ip}\naddi {ip} 2 {ip}\nseti 0 0 0\naddr 0 {ip} {ip}\n"
+        ));
+
+        assert_eq!(program.flow(0), Flow::Jump(3));
+        assert_eq!(program.flow(2), Flow::Skip(0));
+        assert!(program.disassemble().contains("   0  "));
+        assert_eq!(
+            program.decompile().to_string(),
+            "halt\nr0 = 0\ngoto 3 + r0\n"
+        );
+        assert_eq!(assemble(&program.to_assembly()).unwrap(), program);
+    }
+}
+
+#[test]
+fn leaves_unstructured_jumps_as_they_are() {
+    // A condition that only skips one instruction, and a loop that is entered halfway.
+    let program = program(
+        "#ip 5
+gtri 0 3 1
+addr 1 5 5
+addi 0 1 0
+seti 4 0 5
+addi 0 1 0
+addi 2 1 2
+eqri 2 4 1
+addr 5 1 5
+seti 3 0 5
+",
+    );
+
+    assert_eq!(
+        program.decompile().to_string(),
+        "if r0 <= 3 {
+    r0 += 1
+}
+goto L5
+L4:
+r0 += 1
+L5:
+r2 += 1
+if r2 != 4 goto L4
+"
+    );
+
+    let mut machine = Machine::<6>::new(program);
+    machine.run().unwrap();
+    assert_eq!(machine.registers[0], 4);
+    assert_eq!(machine.registers[2], 4);
+}
+
+#[test]
+fn assembles_labels_and_register_names() {
+    let program = assemble(
+        "#ip 5
+#reg count 2          ; `count` is register 2
+        seti 0 0 count
+again:  addi count 1 count
+        eqri count 10 r1
+        addr ip r1 ip
+        jmp again       ; seti again-1 0 ip
+        seti again 0 0
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        program.to_string(),
+        "#ip 5\nseti 0 0 2\naddi 2 1 2\neqri 2 10 1\naddr 5 1 5\nseti 0 0 5\nseti 1 0 0\n"
+    );
+    assert_eq!(
+        program.to_assembly(),
+        "#ip 5
+    seti 0 0 r2
+L1:
+    addi r2 1 r2
+    eqri r2 10 r1
+    addr ip r1 ip
+    seti 0 0 ip
+L5:
+    seti 1 0 r0
+"
+    );
+
+    let mut machine = Machine::<6>::new(program);
+    machine.run().unwrap();
+    assert_eq!(machine.registers[2], 10);
+}
+
+#[test]
+fn reports_what_does_not_assemble() {
+    let error = |source| assemble(source).unwrap_err().to_string();
+
+    assert!(error("seti 0 0 r1\naddi r1 far r1").contains("unknown label far"));
+    assert!(error("addr r1 count r1").contains("unknown register count"));
+    assert!(error("addr ip 1 r1").contains("ip is not bound"));
+    assert!(error("#ip 1\nstart: jmp start").contains("cannot jump to the first instruction"));
+    assert!(error("a: seti 0 0 0\na: seti 0 0 0").contains("label a is defined twice"));
+    assert!(error("#ip 1\n#ip 2").contains("already bound"));
+    assert!(error("#reg 2").contains("#reg name N"));
+
+    // Every line that does not assemble is reported, none is left out.
+    let Err(Error::Rejected(rejected)) =
+        assemble("seti 0 0 0\nfoo 1 2 3\nend: seti 0 0 0\njmp end")
+    else {
+        panic!("expected the bad lines to be rejected");
+    };
+
+    let lines: Vec<_> = rejected
+        .iter()
+        .map(|err| match err {
+            Error::Parse { line, .. } => *line,
+            _ => 0,
+        })
+        .collect();
+
+    assert_eq!(lines, [2, 4]);
+}
+
+#[test]
+fn assembly_round_trips() {
+    let mut rng = Rng(0x2018_1216);
+
+    for _ in 0..500 {
+        let program = rng.program();
+
+        assert_eq!(assemble(&program.to_assembly()).unwrap(), program);
+        assert_eq!(assemble(&program.to_string()).unwrap(), program);
+    }
+
+    assert_eq!(
+        assemble(&program(SOURCE).to_assembly()).unwrap(),
+        program(SOURCE)
+    );
+}
+
+#[test]
+fn disassembly_finds_the_labels_jumped_to() {
+    let mut rng = Rng(0x1219);
+    let mut jumped = 0;
+
+    for _ in 0..500 {
+        let (source, jumps) = rng.source();
+        jumped += jumps.len();
+        let program = assemble(&source).unwrap();
+        let listing = program.disassemble();
+        let len = program.instructions.len();
+
+        for pc in 0..len {
+            let expected = match jumps.iter().find(|&&(from, _)| from == pc) {
+                Some(&(_, target)) => Flow::Jump(target),
+                None => Flow::Next,
+            };
+
+            assert_eq!(program.flow(pc), expected, "{pc} in\n{source}");
+        }
+
+        assert_eq!(
+            program.jump_targets(),
+            jumps
+                .iter()
+                .map(|&(_, target)| target)
+                .filter(|&target| target < len)
+                .collect(),
+            "{source}"
+        );
+
+        for &(pc, target) in &jumps {
+            let label = match target {
+                _ if target == len => "halt".to_string(),
+                _ => format!("L{target}"),
+            };
+
+            let jump = format!("seti {} 0 5", target - 1);
+            assert!(
+                listing.contains(&format!("{pc:>4}  {jump:<16}  goto {label}\n")),
+                "{listing}"
+            );
+
+            if target < len {
+                assert!(listing.contains(&format!("\nL{target}:\n")), "{listing}");
+            }
+        }
+    }
+
+    assert!(jumped > 500, "only {jumped} jumps");
+}
+
+#[test]
+fn encodes_with_a_numbering() {
+    let mut rng = Rng(0x0016);
+    let mut numbering = OPCODES;
+
+    for i in (1..numbering.len()).rev() {
+        numbering.swap(i, rng.below(i + 1));
+    }
+
+    let program = rng.program();
+    let encoded = program.encode(&numbering).unwrap();
+    let decoded: Option<Vec<_>> = encoded
+        .iter()
+        .map(|&numbers| Instruction::decode(numbers, &numbering))
+        .collect();
+
+    assert_eq!(decoded.unwrap(), program.instructions);
+    assert_eq!(program.encode(&[Opcode::Seti]), None);
+    assert_eq!(
+        Instruction::<usize>::decode([16, 0, 0, 0], &numbering),
+        None
+    );
+}
+
+#[test]
+fn encodes_with_the_numbering_deduced_from_the_samples() {
+    let manual = Manual::parse(MANUAL, &mut Parser::new(ParseMode::Strict)).unwrap();
+    let numbering = samples::deduce_opcode_name(&manual.samples).unwrap();
+
+    let mut sorted = numbering;
+    sorted.sort();
+    assert_eq!(sorted, OPCODES);
+
+    // The manual's own program decodes and encodes back to the same numbers.
+    let numbered = manual.program.unwrap();
+    let program = Program::from(
+        numbered
+            .iter()
+            .map(|&numbers| Instruction::decode(numbers, &numbering).unwrap())
+            .collect::<Vec<_>>(),
+    );
+    assert_eq!(program.encode(&numbering).unwrap(), numbered);
+
+    let assembled = assemble("#reg total 0\nseti 7 0 total\nmuli total 6 total\n").unwrap();
+    let encoded = assembled.encode(&numbering).unwrap();
+    let number = |opcode| numbering.iter().position(|&n| n == opcode).unwrap();
+
+    assert_eq!(
+        encoded,
+        [
+            [number(Opcode::Seti), 7, 0, 0],
+            [number(Opcode::Muli), 0, 6, 0]
+        ]
+    );
+
+    let mut machine = Machine::<{ samples::REGISTERS }>::new(assembled);
+    machine.run().unwrap();
+    assert_eq!(machine.registers[0], 42);
+}
@@ -0,0 +1,361 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, UNIX_EPOCH};
+
+use advent_of_code_2018::client::{
+    get_input, unlock_time, Cache, CachePolicy, Client, ClientError, Retry,
+};
+use advent_of_code_2018::input::year_from_args;
+use advent_of_code_2018::setup::{self, SetupError};
+use advent_of_code_2018::{ArgError, Expected, InputSource};
+
+use common::{no_rate_limit, temp_dir, Response, Server};
+
+fn client(server: &Server) -> Client {
+    Client::new("secret-session")
+        .with_base_url(&server.url)
+        .with_rate_limit(no_rate_limit())
+        .with_retry(Retry {
+            attempts: 3,
+            backoff: Duration::ZERO,
+        })
+}
+
+#[test]
+fn fetches_once_then_serves_from_cache() {
+    let server = Server::start(|_| Response::new(200, "1\n2\n"));
+    let cache = Cache::new(temp_dir("fetch-once"));
+    let client = client(&server);
+
+    for _ in 0..2 {
+        let input = get_input(&client, &cache, 2018, 1, CachePolicy::Prefer).unwrap();
+        assert_eq!(input, "1\n2\n");
+    }
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/2018/day/1/input");
+    assert_eq!(requests[0].header("cookie"), Some("session=secret-session"));
+
+    let path = cache.path(2018, 1, &client.session_hash());
+    assert!(path.exists());
+    assert!(!path.to_string_lossy().contains("secret-session"));
+}
+
+#[test]
+fn offline_never_fetches() {
+    let server = Server::start(|_| Response::new(200, "fresh"));
+    let cache = Cache::new(temp_dir("offline"));
+    let client = client(&server);
+
+    assert!(matches!(
+        get_input(&client, &cache, 2018, 2, CachePolicy::Offline),
+        Err(ClientError::NotCached { year: 2018, day: 2 })
+    ));
+
+    cache
+        .put(2018, 2, &client.session_hash(), "cached")
+        .unwrap();
+
+    let input = get_input(&client, &cache, 2018, 2, CachePolicy::Offline).unwrap();
+    assert_eq!(input, "cached");
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn refresh_replaces_cached_input() {
+    let server = Server::start(|_| Response::new(200, "fresh"));
+    let cache = Cache::new(temp_dir("refresh"));
+    let client = client(&server);
+
+    cache.put(2018, 3, &client.session_hash(), "stale").unwrap();
+
+    let input = get_input(&client, &cache, 2018, 3, CachePolicy::Refresh).unwrap();
+    assert_eq!(input, "fresh");
+    assert_eq!(
+        cache.get(2018, 3, &client.session_hash()).unwrap(),
+        Some("fresh".to_string())
+    );
+}
+
+#[test]
+fn failed_fetch_is_not_cached() {
+    let server = Server::start(|_| Response::new(404, "Not found"));
+    let cache = Cache::new(temp_dir("not-found"));
+    let client = client(&server);
+
+    assert!(matches!(
+        get_input(&client, &cache, 2018, 4, CachePolicy::Prefer),
+        Err(ClientError::NotFound { .. })
+    ));
+    assert_eq!(cache.get(2018, 4, &client.session_hash()).unwrap(), None);
+}
+
+#[test]
+fn retries_server_errors_until_they_pass() {
+    let calls = AtomicUsize::new(0);
+    let server = Server::start(move |_| match calls.fetch_add(1, Ordering::SeqCst) {
+        0 | 1 => Response::new(502, "Bad gateway"),
+        _ => Response::new(200, "1\n"),
+    });
+
+    let input = client(&server).fetch_input(2018, 6).unwrap();
+    assert_eq!(input, "1\n");
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[test]
+fn gives_up_after_the_last_retry() {
+    let server = Server::start(|_| Response::new(500, "Oops"));
+
+    assert!(matches!(
+        client(&server).fetch_input(2018, 7),
+        Err(ClientError::Server { status: 500, .. })
+    ));
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[test]
+fn expired_session_is_unauthorized() {
+    let login_page = "<!DOCTYPE html>\n<html lang=\"en-us\">\n<body>[Log In]</body>\n</html>\n";
+    let server = Server::start(move |request| match request.path.as_str() {
+        "/2018/day/8/input" => Response::new(400, "Please log in to get your puzzle input."),
+        _ => Response::new(200, login_page),
+    });
+    let cache = Cache::new(temp_dir("login-page"));
+    let client = client(&server);
+
+    for day in [8, 9] {
+        assert!(matches!(
+            get_input(&client, &cache, 2018, day, CachePolicy::Prefer),
+            Err(ClientError::Unauthorized { .. })
+        ));
+        assert_eq!(cache.get(2018, day, &client.session_hash()).unwrap(), None);
+    }
+
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn locked_puzzle_is_not_yet_available() {
+    let server = Server::start(|_| {
+        Response::new(
+            404,
+            "Please don't repeatedly request this endpoint before it unlocks!",
+        )
+    });
+
+    let Err(ClientError::NotYetAvailable { unlocks_at, .. }) = client(&server).fetch_input(2099, 1)
+    else {
+        panic!("expected the puzzle to be locked");
+    };
+
+    assert_eq!(unlocks_at, unlock_time(2099, 1));
+    assert_eq!(
+        unlock_time(2018, 1),
+        UNIX_EPOCH + Duration::from_secs(1_543_640_400)
+    );
+}
+
+/// A directory with a single `template.rs` for setting up days.
+fn src_dir_with_template(name: &str, template: &str) -> (PathBuf, PathBuf) {
+    let src_dir = temp_dir(name);
+    let template_path = src_dir.join("template.rs");
+    fs::write(&template_path, template).unwrap();
+
+    (src_dir, template_path)
+}
+
+#[test]
+fn refreshing_input_leaves_solution_alone() {
+    let (src_dir, template) = src_dir_with_template("setup", "const DAY: u8 = {{day}};\n");
+
+    let main_path = setup::create_day(&src_dir, &template, 2018, 5).unwrap();
+    assert_eq!(
+        fs::read_to_string(&main_path).unwrap(),
+        "const DAY: u8 = 5;\n"
+    );
+    assert!(setup::create_day(&src_dir, &template, 2018, 5).is_err());
+
+    fs::write(&main_path, "// solved\n").unwrap();
+
+    let input_path = src_dir.join("day05/input.txt");
+    setup::write_input(&input_path, "old").unwrap();
+    setup::write_input(&input_path, "new").unwrap();
+
+    assert_eq!(fs::read_to_string(&input_path).unwrap(), "new");
+    assert_eq!(fs::read_to_string(&main_path).unwrap(), "// solved\n");
+}
+
+#[test]
+fn other_years_get_their_own_directory() {
+    let (src_dir, template) = src_dir_with_template(
+        "setup-year",
+        "const YEAR: u16 = {{year}};\nconst DAY: u8 = {{day_padded}};\n",
+    );
+
+    let main_path = setup::create_day(&src_dir, &template, 2019, 1).unwrap();
+    assert_eq!(main_path, src_dir.join("2019/day01/main.rs"));
+    assert_eq!(
+        fs::read_to_string(&main_path).unwrap(),
+        "const YEAR: u16 = 2019;\nconst DAY: u8 = 01;\n"
+    );
+
+    let source = InputSource::Day {
+        dir: Some(src_dir.clone()),
+        example: false,
+    };
+    assert_eq!(
+        source.path(2019, 1),
+        Some(src_dir.join("2019/day01/input.txt"))
+    );
+    assert_eq!(source.path(2018, 1), Some(src_dir.join("day01/input.txt")));
+}
+
+#[test]
+fn year_flag_tells_a_bad_number_from_an_early_year() {
+    let year = |value: &str| year_from_args(&mut vec!["--year".to_string(), value.to_string()]);
+
+    assert_eq!(year("2015").unwrap(), 2015);
+    assert!(matches!(year("abc"), Err(ArgError::InvalidYear(_))));
+    assert!(matches!(year("2014"), Err(ArgError::YearTooEarly(2014))));
+}
+
+#[test]
+fn template_only_replaces_placeholders() {
+    let path = Path::new("template.rs");
+    let template = "pub struct Day{{day_padded}};\nconst LIMIT: u32 = 1_000_000 + 100;\n";
+
+    assert_eq!(
+        setup::render(template, path, 2018, 7).unwrap(),
+        "pub struct Day07;\nconst LIMIT: u32 = 1_000_000 + 100;\n"
+    );
+    assert!(matches!(
+        setup::render("{{ month }}", path, 2018, 7),
+        Err(SetupError::Scaffold { .. })
+    ));
+    assert!(setup::render("{{day", path, 2018, 7).is_err());
+}
+
+#[test]
+fn every_template_renders() {
+    let template_dir = setup::default_template_dir();
+    let templates = setup::templates(&template_dir).unwrap();
+
+    assert!(templates.contains(&setup::DEFAULT_TEMPLATE.to_string()));
+
+    for name in templates {
+        let path = template_dir.join(format!("{name}.rs"));
+        let rendered = setup::render(&fs::read_to_string(&path).unwrap(), &path, 2018, 3).unwrap();
+
+        assert!(rendered.contains("pub struct Day03;"), "{name}");
+        assert!(rendered.contains("const DAY: u8 = 3;"), "{name}");
+    }
+}
+
+#[test]
+fn scaffolding_a_day_keeps_the_tests_green() {
+    let (src_dir, _) = src_dir_with_template("scaffold-green", "");
+    let template = setup::default_template_dir().join(format!("{}.rs", setup::DEFAULT_TEMPLATE));
+    let tests_path = src_dir.join("days.rs");
+    fs::copy(setup::default_tests_path(), &tests_path).unwrap();
+
+    setup::create_day(&src_dir, &template, 2015, 1).unwrap();
+    assert!(setup::register_test(&tests_path, 2015, 1).unwrap());
+
+    // The new day has no ex.txt, and the template's answers are stubs, so its example test must
+    // not run until it is solved.
+    assert!(!src_dir.join("2015/day01/ex.txt").exists());
+
+    let tests = fs::read_to_string(&tests_path).unwrap();
+    let lines: Vec<_> = tests.lines().collect();
+    let test = lines
+        .iter()
+        .position(|line| *line == "fn y2015_day01_example() {")
+        .unwrap();
+
+    assert_eq!(lines[test - 2], "#[test]");
+    assert_eq!(
+        lines[test - 1],
+        format!("#[ignore = \"{}\"]", setup::EXAMPLE_TEST_IGNORED)
+    );
+    assert_eq!(tests.matches("y2015_day01").count(), 2);
+}
+
+#[test]
+fn registers_new_days_once() {
+    let dir = temp_dir("register");
+    let days_path = dir.join("days.rs");
+    let tests_path = dir.join("tests.rs");
+
+    fs::write(
+        &days_path,
+        "#[path = \"bin/day01/main.rs\"]\npub mod day01;\n\npub const DAYS: &[Day] = &[\n    day::<day01::Day01>(),\n];\n",
+    )
+    .unwrap();
+    fs::write(&tests_path, "use advent_of_code_2018::days::*;\n").unwrap();
+
+    assert!(setup::register_day(&days_path, 2018, 2).unwrap());
+    assert!(setup::register_day(&days_path, 2019, 2).unwrap());
+    assert!(!setup::register_day(&days_path, 2018, 2).unwrap());
+    assert!(setup::register_test(&tests_path, 2018, 2).unwrap());
+    assert!(!setup::register_test(&tests_path, 2018, 2).unwrap());
+
+    assert_eq!(
+        fs::read_to_string(&days_path).unwrap(),
+        "#[path = \"bin/day01/main.rs\"]
+pub mod day01;
+#[path = \"bin/day02/main.rs\"]
+pub mod day02;
+#[path = \"bin/2019/day02/main.rs\"]
+pub mod y2019_day02;
+
+pub const DAYS: &[Day] = &[
+    day::<day01::Day01>(),
+    day::<day02::Day02>(),
+    day::<y2019_day02::Day02>(),
+];
+"
+    );
+    assert_eq!(
+        fs::read_to_string(&tests_path).unwrap(),
+        "use advent_of_code_2018::days::*;
+
+#[test]
+#[ignore = \"fill in ex.toml, then remove this line\"]
+fn day02_example() {
+    check_example::<day02::Day02>();
+}
+"
+    );
+}
+
+#[test]
+fn example_stub_keeps_expected_answers() {
+    let day_dir = temp_dir("example");
+
+    setup::write_example(&day_dir, "1\n2\n", Some("3")).unwrap();
+    assert_eq!(
+        fs::read_to_string(day_dir.join("ex.txt")).unwrap(),
+        "1\n2\n"
+    );
+
+    let expected = Expected::load(&day_dir.join("ex.toml")).unwrap();
+    assert_eq!(expected.part1.as_deref(), Some("3"));
+    assert_eq!(expected.part2, None);
+
+    fs::write(day_dir.join("ex.toml"), "part1 = \"4\"\n").unwrap();
+    setup::write_example(&day_dir, "2\n2\n", Some("5")).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(day_dir.join("ex.txt")).unwrap(),
+        "2\n2\n"
+    );
+    assert_eq!(
+        fs::read_to_string(day_dir.join("ex.toml")).unwrap(),
+        "part1 = \"4\"\n"
+    );
+}
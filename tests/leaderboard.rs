@@ -0,0 +1,121 @@
+mod common;
+
+use std::time::Duration;
+
+use advent_of_code_2018::client::{self, Cache, Client, ClientError, Retry};
+use advent_of_code_2018::leaderboard::Leaderboard;
+
+use common::{no_rate_limit, temp_dir, Response, Server};
+
+const JSON: &str = include_str!("fixtures/leaderboard.json");
+
+fn client(server: &Server) -> Client {
+    Client::new("secret-session")
+        .with_base_url(&server.url)
+        .with_rate_limit(no_rate_limit())
+        .with_retry(Retry {
+            attempts: 1,
+            backoff: Duration::ZERO,
+        })
+}
+
+fn names(leaderboard: &Leaderboard) -> Vec<String> {
+    leaderboard
+        .ranked()
+        .into_iter()
+        .map(|member| member.display_name())
+        .collect()
+}
+
+#[test]
+fn ranks_by_score_then_stars_then_last_star() {
+    let leaderboard = Leaderboard::from_json(JSON).unwrap();
+
+    assert_eq!(leaderboard.last_day(), 2);
+    assert_eq!(
+        names(&leaderboard),
+        ["bob", "alice", "(anonymous user #300)"]
+    );
+    assert_eq!(
+        leaderboard.ranking(),
+        "                                       00
+Rank Score Stars Name                  12
+   1     6     3 bob                   *+
+   2     6     3 alice                 *+
+   3     2     1 (anonymous user #300) +.
+"
+    );
+}
+
+#[test]
+fn reports_part_two_deltas_and_timelines() {
+    let leaderboard = Leaderboard::from_json(JSON).unwrap();
+    let alice = &leaderboard.members["100"];
+
+    assert_eq!(alice.delta(1), Some(Duration::from_secs(128)));
+    assert_eq!(alice.delta(2), None);
+    assert_eq!(
+        leaderboard.deltas(2018),
+        "     Part 1    Part 2     Delta  Name
+Day 01
+   00:03:12  00:05:20 +00:02:08  alice
+   00:01:40  00:06:40 +00:05:00  bob
+"
+    );
+    assert!(leaderboard.timelines(2018).ends_with(
+        "(anonymous user #300) (2 points, 1 stars)
+  2018-12-01 05:16:40 UTC  day 01 part 1  00:16:40 after unlock
+"
+    ));
+}
+
+#[test]
+fn fetches_at_most_every_fifteen_minutes() {
+    let server = Server::start(|_| Response::new(200, JSON));
+    let client = client(&server);
+    let cache = Cache::new(temp_dir("leaderboard"));
+
+    let (first, _) = client::get_leaderboard(&client, &cache, 2018, 100, false).unwrap();
+    let (second, _) = client::get_leaderboard(&client, &cache, 2018, 100, false).unwrap();
+    let (offline, _) = client::get_leaderboard(&client, &cache, 2018, 100, true).unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/2018/leaderboard/private/view/100.json");
+    assert_eq!(requests[0].header("cookie"), Some("session=secret-session"));
+    assert_eq!(first, second);
+    assert_eq!(first, offline);
+
+    assert!(matches!(
+        client::get_leaderboard(&client, &cache, 2018, 200, true),
+        Err(ClientError::LeaderboardNotCached {
+            year: 2018,
+            id: 200
+        })
+    ));
+}
+
+#[test]
+fn login_page_and_garbage_are_not_cached() {
+    let server = Server::start(|request| {
+        if request.path.contains("/100.json") {
+            Response::new(200, "<!DOCTYPE html><html>Log in</html>")
+        } else {
+            Response::new(200, "{\"nope\": true}")
+        }
+    });
+    let client = client(&server);
+    let cache = Cache::new(temp_dir("leaderboard-invalid"));
+
+    assert!(matches!(
+        client::get_leaderboard(&client, &cache, 2018, 100, false),
+        Err(ClientError::Unauthorized { .. })
+    ));
+    assert!(matches!(
+        client::get_leaderboard(&client, &cache, 2018, 200, false),
+        Err(ClientError::UnexpectedResponse { .. })
+    ));
+    assert!(!cache
+        .leaderboard_path(2018, 200, &client.session_hash())
+        .exists());
+}
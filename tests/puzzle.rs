@@ -0,0 +1,89 @@
+mod common;
+
+use std::time::Duration;
+
+use advent_of_code_2018::client::{Client, ClientError, Retry};
+use advent_of_code_2018::puzzle::{self, CodeBlock, Description};
+
+use common::{no_rate_limit, Response, Server};
+
+const PAGE: &str = include_str!("fixtures/puzzle.html");
+const MARKDOWN: &str = include_str!("fixtures/puzzle.md");
+
+fn client(server: &Server) -> Client {
+    Client::new("secret-session")
+        .with_base_url(&server.url)
+        .with_rate_limit(no_rate_limit())
+        .with_retry(Retry {
+            attempts: 1,
+            backoff: Duration::ZERO,
+        })
+}
+
+#[test]
+fn converts_puzzle_page_to_markdown() {
+    let description = Description::from_html(PAGE, "https://adventofcode.com").unwrap();
+
+    assert!(description.has_part2());
+    assert_eq!(description.to_markdown(), MARKDOWN);
+    assert!(puzzle::is_complete(MARKDOWN));
+}
+
+#[test]
+fn part_one_only_until_it_is_solved() {
+    let part1_only = PAGE
+        .split("<p>Your puzzle answer")
+        .next()
+        .unwrap()
+        .to_string();
+    let server = Server::start(move |_| Response::new(200, part1_only.as_str()));
+
+    let description = client(&server).fetch_description(2018, 1).unwrap();
+
+    assert_eq!(server.requests()[0].path, "/2018/day/1");
+    assert!(!description.has_part2());
+    assert!(!puzzle::is_complete(&description.to_markdown()));
+}
+
+#[test]
+fn page_without_statement_is_unexpected() {
+    let server = Server::start(|_| Response::new(200, "<html><body>Nothing</body></html>"));
+
+    assert!(matches!(
+        client(&server).fetch_description(2018, 1),
+        Err(ClientError::UnexpectedResponse { .. })
+    ));
+}
+
+#[test]
+fn picks_the_example_among_code_blocks() {
+    let blocks = puzzle::code_blocks(PAGE);
+
+    assert_eq!(
+        blocks,
+        [
+            CodeBlock {
+                part: 1,
+                text: "+1\n-2\n+3\n+1\n".to_string(),
+                introduced_as_example: true,
+            },
+            CodeBlock {
+                part: 2,
+                text: "7 < 8\n".to_string(),
+                introduced_as_example: false,
+            },
+        ]
+    );
+    assert_eq!(puzzle::likely_example(&blocks), Some(0));
+    assert_eq!(puzzle::likely_example(&blocks[1..]), Some(0));
+    assert_eq!(puzzle::likely_example(&[]), None);
+    assert_eq!(puzzle::example_answer(PAGE, 0), Some("3".to_string()));
+    assert_eq!(puzzle::example_answer(PAGE, 1), None);
+    assert_eq!(puzzle::example_answer(PAGE, 2), None);
+
+    // An answer after a later block is not this block's.
+    let unanswered = "<article class=\"day-desc\"><pre><code>1\n</code></pre><p>Then:</p>\
+        <pre><code>2\n</code></pre><p>gives <code><em>4</em></code>.</p></article>";
+    assert_eq!(puzzle::example_answer(unanswered, 0), None);
+    assert_eq!(puzzle::example_answer(unanswered, 1), Some("4".to_string()));
+}
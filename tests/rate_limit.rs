@@ -0,0 +1,123 @@
+mod common;
+
+use std::fs;
+use std::time::{Duration, Instant, SystemTime};
+
+use advent_of_code_2018::client::{Client, ClientError, Retry};
+use advent_of_code_2018::rate_limit::RateLimit;
+
+use common::{temp_dir, Response, Server};
+
+fn client(server: &Server, rate_limit: RateLimit) -> Client {
+    Client::new("secret-session")
+        .with_base_url(&server.url)
+        .with_rate_limit(rate_limit)
+        .with_retry(Retry {
+            attempts: 1,
+            backoff: Duration::ZERO,
+        })
+}
+
+fn user_page(request: &common::Request) -> Response {
+    if request.path == "/settings" {
+        Response::new(200, "<div class=\"user\">example user</div>")
+    } else {
+        Response::new(404, "Not found")
+    }
+}
+
+#[test]
+fn logs_every_request_without_the_session() {
+    let server = Server::start(user_page);
+    let log_path = temp_dir("request-log").join("requests.log");
+    let client = client(
+        &server,
+        RateLimit {
+            min_interval: Duration::ZERO,
+            ..RateLimit::new(&log_path)
+        },
+    );
+
+    client.fetch_user().unwrap();
+    assert!(matches!(
+        client.fetch_input(2018, 1),
+        Err(ClientError::NotFound { .. })
+    ));
+
+    let log = fs::read_to_string(&log_path).unwrap();
+    let lines: Vec<_> = log.lines().collect();
+
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].ends_with(&format!(" UTC GET {}/settings 200", server.url)));
+    assert!(lines[1].ends_with(&format!(" UTC GET {}/2018/day/1/input 404", server.url)));
+    assert!(!log.contains("secret-session"));
+}
+
+#[test]
+fn refuses_bursts() {
+    let server = Server::start(user_page);
+    let client = client(
+        &server,
+        RateLimit {
+            min_interval: Duration::ZERO,
+            burst_limit: 2,
+            burst_window: Duration::from_secs(60 * 60),
+            ..RateLimit::new(temp_dir("request-burst").join("requests.log"))
+        },
+    );
+
+    client.fetch_user().unwrap();
+    client.fetch_user().unwrap();
+
+    let Err(ClientError::RateLimited { retry_at }) = client.fetch_user() else {
+        panic!("the third request was sent");
+    };
+
+    assert_eq!(server.requests().len(), 2);
+    assert!(retry_at > SystemTime::now() + Duration::from_secs(59 * 60));
+}
+
+#[test]
+fn waits_between_invocations() {
+    let server = Server::start(user_page);
+    let rate_limit = RateLimit {
+        min_interval: Duration::from_millis(300),
+        ..RateLimit::new(temp_dir("request-interval").join("requests.log"))
+    };
+
+    let start = Instant::now();
+
+    // Separate clients, as separate runs of a binary would have, only share the log.
+    client(&server, rate_limit.clone()).fetch_user().unwrap();
+    client(&server, rate_limit).fetch_user().unwrap();
+
+    assert!(start.elapsed() >= Duration::from_millis(300));
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn keeps_the_response_when_the_log_cannot_be_written() {
+    let log_dir = temp_dir("request-log-unwritable").join("logs");
+    let blocked = log_dir.clone();
+
+    // Once the request is in, a file takes the log directory's place.
+    let server = Server::start(move |request| {
+        fs::write(&blocked, "").unwrap();
+        user_page(request)
+    });
+    let client = client(
+        &server,
+        RateLimit {
+            min_interval: Duration::ZERO,
+            ..RateLimit::new(log_dir.join("requests.log"))
+        },
+    );
+
+    assert_eq!(client.fetch_user().unwrap(), "example user");
+    assert!(log_dir.is_file());
+    assert!(matches!(
+        client.take_warnings().as_slice(),
+        [ClientError::Cache { .. }]
+    ));
+    assert!(client.take_warnings().is_empty());
+}
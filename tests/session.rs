@@ -0,0 +1,67 @@
+mod common;
+
+use advent_of_code_2018::client::{Client, ClientError};
+use advent_of_code_2018::session::SessionStore;
+
+use common::{no_rate_limit, temp_dir, Response, Server};
+
+#[test]
+fn reports_the_logged_in_user() {
+    let server = Server::start(|_| {
+        Response::new(
+            200,
+            "<header><div class=\"user\">example user <span class=\"star-count\">4*</span></div></header>",
+        )
+    });
+
+    let user = Client::new("secret-session")
+        .with_base_url(&server.url)
+        .with_rate_limit(no_rate_limit())
+        .fetch_user()
+        .unwrap();
+
+    assert_eq!(user, "example user");
+
+    let requests = server.requests();
+    assert_eq!(requests[0].path, "/settings");
+    assert_eq!(requests[0].header("cookie"), Some("session=secret-session"));
+}
+
+#[test]
+fn logged_out_page_is_unauthorized() {
+    let server = Server::start(|_| {
+        Response::new(
+            200,
+            "<!DOCTYPE html><html><body><p>To play, please identify yourself via one of these services:</p></body></html>",
+        )
+    });
+
+    assert!(matches!(
+        Client::new("expired")
+            .with_base_url(&server.url)
+            .with_rate_limit(no_rate_limit())
+            .fetch_user(),
+        Err(ClientError::Unauthorized { .. })
+    ));
+}
+
+#[test]
+fn store_round_trips_privately() {
+    let path = temp_dir("sessions").join("sessions.toml");
+
+    let mut store = SessionStore::default();
+    store.add("personal", "aaa", None);
+    store.add("team", "bbb", None);
+    store.record_check("team", "team account");
+    store.save(&path).unwrap();
+
+    assert_eq!(SessionStore::load(&path).unwrap(), store);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
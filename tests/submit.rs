@@ -0,0 +1,74 @@
+mod common;
+
+use std::time::Duration;
+
+use advent_of_code_2018::client::{Client, ClientError};
+use advent_of_code_2018::submit::Outcome;
+
+use common::{no_rate_limit, Response, Server};
+
+fn page(text: &str) -> String {
+    format!("<!DOCTYPE html>\n<html><body><main>\n<article><p>{text}</p></article>\n</main></body></html>")
+}
+
+#[test]
+fn posts_answer_with_session() {
+    let server = Server::start(|_| Response::new(200, page("That's the right answer!")));
+    let client = Client::new("secret-session")
+        .with_base_url(&server.url)
+        .with_rate_limit(no_rate_limit());
+
+    assert_eq!(
+        client.submit(2018, 1, 2, "71892").unwrap(),
+        Outcome::Correct
+    );
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/2018/day/1/answer");
+    assert_eq!(requests[0].body, "level=2&answer=71892");
+    assert_eq!(requests[0].header("cookie"), Some("session=secret-session"));
+    assert!(requests[0].header("user-agent").is_some());
+}
+
+#[test]
+fn reports_wrong_and_rate_limited_answers() {
+    let server = Server::start(|request| {
+        if request.body.ends_with("answer=1") {
+            Response::new(
+                200,
+                page("That's not the right answer; your answer is too low."),
+            )
+        } else {
+            Response::new(
+                200,
+                page("You gave an answer too recently.  You have 2m 0s left to wait."),
+            )
+        }
+    });
+    let client = Client::new("secret-session")
+        .with_base_url(&server.url)
+        .with_rate_limit(no_rate_limit());
+
+    assert_eq!(client.submit(2018, 3, 1, "1").unwrap(), Outcome::TooLow);
+    assert_eq!(
+        client.submit(2018, 3, 1, "2").unwrap(),
+        Outcome::RateLimited {
+            wait: Some(Duration::from_secs(120))
+        }
+    );
+}
+
+#[test]
+fn unrecognised_page_is_an_error() {
+    let server = Server::start(|_| Response::new(200, page("Please log in")));
+    let client = Client::new("secret-session")
+        .with_base_url(&server.url)
+        .with_rate_limit(no_rate_limit());
+
+    assert!(matches!(
+        client.submit(2018, 4, 1, "42"),
+        Err(ClientError::UnexpectedResponse { .. })
+    ));
+}